@@ -0,0 +1,394 @@
+// This file is part of the uutils procps package.
+//
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+// Follow mode: report the processes as they start matching, and as they exit
+
+use crate::process::ProcessInformation;
+use crate::process_matcher::{self, Settings};
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::time::Duration;
+use uucore::error::UResult;
+
+/// How often `/proc` is rescanned
+const RESCAN_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The processes reported as matching, by PID, with their start time, which
+/// tells a process from a later one reusing its PID
+type Matched = BTreeMap<usize, Option<u64>>;
+
+/// Keep running, printing `+PID name` for each process that matches, those
+/// already running first, and `-PID` once a matched process is gone.
+///
+/// `/proc` is rescanned periodically. As root, the processes the kernel
+/// reports as they fork, exec and exit are also checked right away, so that
+/// only those gone before they could be read are missed; otherwise those
+/// that start and exit between two rescans are.
+/// Returns once stdout is closed.
+pub(crate) fn follow(settings: &Settings) -> UResult<()> {
+    #[cfg(target_os = "linux")]
+    let events = proc_events::ProcEvents::subscribe();
+    let mut matched = Matched::new();
+    let mut ever_matched = false;
+    let mut stdout = io::stdout().lock();
+
+    loop {
+        let mut pids = process_matcher::find_matching_pids(settings)?;
+        // Nobody is listening anymore
+        if rescanned(&mut stdout, &mut matched, &mut pids).is_err() {
+            break;
+        }
+        ever_matched |= !matched.is_empty();
+
+        #[cfg(target_os = "linux")]
+        if let Some(events) = &events {
+            let listening = until_rescan(
+                settings,
+                events,
+                &mut stdout,
+                &mut matched,
+                &mut ever_matched,
+            )?;
+            if !listening {
+                break;
+            }
+            continue;
+        }
+        std::thread::sleep(RESCAN_INTERVAL);
+    }
+
+    // Rescans without matches set it along the way
+    uucore::error::set_exit_code(if ever_matched { 0 } else { 1 });
+    Ok(())
+}
+
+/// Report the changes from `matched` to the processes matching now, `pids`
+fn rescanned(
+    out: &mut impl Write,
+    matched: &mut Matched,
+    pids: &mut [ProcessInformation],
+) -> io::Result<()> {
+    let current: Matched = pids
+        .iter_mut()
+        .map(|pid| (pid.pid, pid.start_time().ok()))
+        .collect();
+    for (pid, started) in &*matched {
+        if current.get(pid) != Some(started) {
+            writeln!(out, "-{pid}")?;
+        }
+    }
+    for pid in pids {
+        if matched.get(&pid.pid) != current.get(&pid.pid) {
+            let name = pid.name().unwrap_or_default();
+            writeln!(out, "+{} {name}", pid.pid)?;
+        }
+    }
+    *matched = current;
+    out.flush()
+}
+
+/// Report the processes the kernel events are about until the next rescan is
+/// due. Returns false once nobody is listening anymore.
+#[cfg(target_os = "linux")]
+fn until_rescan(
+    settings: &Settings,
+    events: &proc_events::ProcEvents,
+    out: &mut impl Write,
+    matched: &mut Matched,
+    ever_matched: &mut bool,
+) -> UResult<bool> {
+    use std::time::Instant;
+
+    let rescan = Instant::now() + RESCAN_INTERVAL;
+    loop {
+        let left = rescan.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return Ok(true);
+        }
+        let Ok(events) = events.wait(left) else {
+            std::thread::sleep(left);
+            return Ok(true);
+        };
+        let (started, exited) = task_ids(settings, &events);
+        let mut pids = process_matcher::find_matching_among(settings, &started)?;
+        if reported(out, matched, &mut pids, &exited).is_err() {
+            return Ok(false);
+        }
+        *ever_matched |= !matched.is_empty();
+    }
+}
+
+/// The IDs of the processes, or with `--lightweight` the threads, that
+/// started and that exited, according to the kernel events
+#[cfg(target_os = "linux")]
+fn task_ids(settings: &Settings, events: &[proc_events::Event]) -> (Vec<usize>, Vec<usize>) {
+    use proc_events::Event;
+
+    let id = |pid: usize, tgid: usize| (settings.threads || pid == tgid).then_some(pid);
+    let mut started = Vec::new();
+    let mut exited = Vec::new();
+    for event in events {
+        match *event {
+            Event::Started { pid, tgid } => started.extend(id(pid, tgid)),
+            Event::Exited { pid, tgid } => exited.extend(id(pid, tgid)),
+        }
+    }
+    (started, exited)
+}
+
+/// Report the processes that just started and match, `pids`, and those of
+/// `matched` that exited and are gone already
+#[cfg(target_os = "linux")]
+fn reported(
+    out: &mut impl Write,
+    matched: &mut Matched,
+    pids: &mut [ProcessInformation],
+    exited: &[usize],
+) -> io::Result<()> {
+    for pid in pids {
+        let started = pid.start_time().ok();
+        if matched.get(&pid.pid) != Some(&started) {
+            let name = pid.name().unwrap_or_default();
+            writeln!(out, "+{} {name}", pid.pid)?;
+            matched.insert(pid.pid, started);
+        }
+    }
+    // Until reaped, the process is still there for the rescans to match
+    for &pid in exited {
+        let Some(&started) = matched.get(&pid) else {
+            continue;
+        };
+        let now = ProcessInformation::from_pid(pid).and_then(|mut now| now.start_time());
+        if now.ok() != started {
+            writeln!(out, "-{pid}")?;
+            matched.remove(&pid);
+        }
+    }
+    out.flush()
+}
+
+/// The process events of the kernel proc connector, over netlink
+#[cfg(target_os = "linux")]
+mod proc_events {
+    use std::io;
+    use std::mem::{self, size_of};
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+    use std::time::Duration;
+    use uucore::libc::{
+        self, nlmsghdr, sockaddr_nl, AF_NETLINK, CN_IDX_PROC, CN_VAL_PROC, MSG_DONTWAIT,
+        NETLINK_CONNECTOR, NLMSG_DONE, PROC_CN_MCAST_LISTEN, PROC_EVENT_EXEC, PROC_EVENT_EXIT,
+        PROC_EVENT_FORK, SOCK_CLOEXEC, SOCK_DGRAM,
+    };
+
+    /// The header of a message to or from the connector, `struct cn_msg`
+    #[repr(C)]
+    struct ConnectorHeader {
+        idx: u32,
+        val: u32,
+        seq: u32,
+        ack: u32,
+        len: u16,
+        flags: u16,
+    }
+
+    /// A netlink message to the connector, with the operation requested
+    #[repr(C)]
+    struct ListenRequest {
+        header: nlmsghdr,
+        connector: ConnectorHeader,
+        op: u32,
+    }
+
+    /// Where the `struct proc_event` of a message is, and its data after the
+    /// kind of event, the CPU and the timestamp
+    const EVENT_OFFSET: usize = size_of::<nlmsghdr>() + size_of::<ConnectorHeader>();
+    const EVENT_DATA_OFFSET: usize = EVENT_OFFSET + 16;
+
+    /// What the kernel reports of a task: `pid` is its ID, that of the thread,
+    /// and `tgid` that of its process.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub(super) enum Event {
+        /// Forked, or exec'd another program
+        Started {
+            pid: usize,
+            tgid: usize,
+        },
+        Exited {
+            pid: usize,
+            tgid: usize,
+        },
+    }
+
+    pub(super) struct ProcEvents(OwnedFd);
+
+    impl ProcEvents {
+        /// None unless root, as listening needs `CAP_NET_ADMIN`, or when the
+        /// kernel has no proc connector.
+        pub(super) fn subscribe() -> Option<Self> {
+            if !rustix::process::geteuid().is_root() {
+                return None;
+            }
+
+            // Safety: plain socket creation, the descriptor is owned from here on
+            let fd =
+                unsafe { libc::socket(AF_NETLINK, SOCK_DGRAM | SOCK_CLOEXEC, NETLINK_CONNECTOR) };
+            if fd < 0 {
+                return None;
+            }
+            // Safety: fd was just opened, and nothing else owns it
+            let socket = unsafe { OwnedFd::from_raw_fd(fd) };
+
+            // Safety: sockaddr_nl is plain data, for which zeroes are valid
+            let mut address: sockaddr_nl = unsafe { mem::zeroed() };
+            address.nl_family = AF_NETLINK as u16;
+            address.nl_groups = CN_IDX_PROC;
+            // Safety: the address is a sockaddr_nl of the given size
+            let bound = unsafe {
+                libc::bind(
+                    socket.as_raw_fd(),
+                    (&raw const address).cast(),
+                    size_of::<sockaddr_nl>() as u32,
+                )
+            };
+            if bound < 0 {
+                return None;
+            }
+
+            let request = ListenRequest {
+                header: nlmsghdr {
+                    nlmsg_len: size_of::<ListenRequest>() as u32,
+                    nlmsg_type: NLMSG_DONE as u16,
+                    nlmsg_flags: 0,
+                    nlmsg_seq: 0,
+                    nlmsg_pid: 0,
+                },
+                connector: ConnectorHeader {
+                    idx: CN_IDX_PROC,
+                    val: CN_VAL_PROC,
+                    seq: 0,
+                    ack: 0,
+                    len: size_of::<u32>() as u16,
+                    flags: 0,
+                },
+                op: PROC_CN_MCAST_LISTEN,
+            };
+            // Safety: the request is valid for its size
+            let sent = unsafe {
+                libc::send(
+                    socket.as_raw_fd(),
+                    (&raw const request).cast(),
+                    size_of::<ListenRequest>(),
+                    0,
+                )
+            };
+            (sent == size_of::<ListenRequest>() as isize).then_some(Self(socket))
+        }
+
+        /// Wait for at most `timeout` for process events, returning those
+        /// that came. None did when interrupted by a signal.
+        pub(super) fn wait(&self, timeout: Duration) -> io::Result<Vec<Event>> {
+            use rustix::event::{poll, PollFd, PollFlags, Timespec};
+            use rustix::io::Errno;
+
+            let timeout = Timespec::try_from(timeout).ok();
+            let mut fds = [PollFd::new(&self.0, PollFlags::IN)];
+            match poll(&mut fds, timeout.as_ref()) {
+                Ok(0) | Err(Errno::INTR) => return Ok(Vec::new()),
+                Ok(_) => {}
+                Err(e) => return Err(e.into()),
+            }
+
+            let mut events = Vec::new();
+            let mut buffer = [0u8; 4096];
+            loop {
+                // Safety: the buffer is valid for its size
+                let received = unsafe {
+                    libc::recv(
+                        self.0.as_raw_fd(),
+                        buffer.as_mut_ptr().cast(),
+                        buffer.len(),
+                        MSG_DONTWAIT,
+                    )
+                };
+                // None left, or some were lost when we fell behind: the
+                // rescans make up for those
+                if received <= 0 {
+                    return Ok(events);
+                }
+                events.extend(parse(&buffer[..received as usize]));
+            }
+        }
+    }
+
+    /// The events of the netlink messages of a datagram
+    fn parse(mut datagram: &[u8]) -> Vec<Event> {
+        let mut events = Vec::new();
+        while let Some(len) = u32_at(datagram, 0) {
+            let len = len as usize;
+            if len < size_of::<nlmsghdr>() || len > datagram.len() {
+                break;
+            }
+            let message = &datagram[..len];
+            let data = |i: usize| u32_at(message, EVENT_DATA_OFFSET + 4 * i).map(|id| id as usize);
+            let event = match u32_at(message, EVENT_OFFSET) {
+                // The parent first, then the child
+                Some(PROC_EVENT_FORK) => data(2)
+                    .zip(data(3))
+                    .map(|(pid, tgid)| Event::Started { pid, tgid }),
+                Some(PROC_EVENT_EXEC) => data(0)
+                    .zip(data(1))
+                    .map(|(pid, tgid)| Event::Started { pid, tgid }),
+                Some(PROC_EVENT_EXIT) => data(0)
+                    .zip(data(1))
+                    .map(|(pid, tgid)| Event::Exited { pid, tgid }),
+                _ => None,
+            };
+            events.extend(event);
+            // Messages are aligned on 4 bytes
+            datagram = &datagram[len.next_multiple_of(4).min(datagram.len())..];
+        }
+        events
+    }
+
+    fn u32_at(bytes: &[u8], offset: usize) -> Option<u32> {
+        let bytes = bytes.get(offset..offset + 4)?;
+        Some(u32::from_ne_bytes(bytes.try_into().ok()?))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        /// A netlink message holding a `struct proc_event` with the given data
+        fn message(what: u32, data: &[u32]) -> Vec<u8> {
+            let mut message = vec![0; EVENT_DATA_OFFSET];
+            message[EVENT_OFFSET..EVENT_OFFSET + 4].copy_from_slice(&what.to_ne_bytes());
+            message.extend(data.iter().flat_map(|id| id.to_ne_bytes()));
+            let len = message.len() as u32;
+            message[..4].copy_from_slice(&len.to_ne_bytes());
+            message
+        }
+
+        #[test]
+        fn test_parse() {
+            let mut datagram = message(PROC_EVENT_FORK, &[10, 10, 11, 11]);
+            datagram.extend(message(PROC_EVENT_EXEC, &[12, 12]));
+            datagram.extend(message(PROC_EVENT_EXIT, &[13, 12, 0, 17, 1, 1]));
+            // Not about starting or exiting, like PROC_EVENT_UID
+            datagram.extend(message(0x4, &[14, 14, 0, 0]));
+            // Cut short
+            datagram.extend(&message(PROC_EVENT_EXEC, &[15, 15])[..EVENT_DATA_OFFSET]);
+
+            assert_eq!(
+                parse(&datagram),
+                [
+                    Event::Started { pid: 11, tgid: 11 },
+                    Event::Started { pid: 12, tgid: 12 },
+                    Event::Exited { pid: 13, tgid: 12 },
+                ]
+            );
+        }
+    }
+}
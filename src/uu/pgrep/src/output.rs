@@ -0,0 +1,224 @@
+// This file is part of the uutils procps package.
+//
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+// Output of the matched processes as a list of fields, in text or JSON
+
+use crate::process::ProcessInformation;
+use std::fmt::Write;
+#[cfg(target_os = "linux")]
+use std::sync::LazyLock;
+#[cfg(unix)]
+use uucore::entries::uid2usr;
+
+/// A column of `--output`, named like the `ps` format specifier it matches
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Field {
+    Pid,
+    Ppid,
+    User,
+    Comm,
+    Args,
+    Start,
+}
+
+enum Value {
+    Number(u64),
+    Text(String),
+}
+
+impl Field {
+    const ALL: [Field; 6] = [
+        Field::Pid,
+        Field::Ppid,
+        Field::User,
+        Field::Comm,
+        Field::Args,
+        Field::Start,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Field::Pid => "pid",
+            Field::Ppid => "ppid",
+            Field::User => "user",
+            Field::Comm => "comm",
+            Field::Args => "args",
+            Field::Start => "start",
+        }
+    }
+
+    /// None when the field can't be read, as for a process that is gone by now
+    fn value(self, process: &mut ProcessInformation) -> Option<Value> {
+        match self {
+            Field::Pid => Some(Value::Number(process.pid as u64)),
+            Field::Ppid => process.ppid().ok().map(Value::Number),
+            Field::User => {
+                let euid = process.euid().ok()?;
+                #[cfg(unix)]
+                let user = uid2usr(euid).unwrap_or_else(|_| euid.to_string());
+                #[cfg(not(unix))]
+                let user = euid.to_string();
+                Some(Value::Text(user))
+            }
+            Field::Comm => process.name().ok().map(Value::Text),
+            Field::Args => Some(Value::Text(process.cmdline.clone())),
+            Field::Start => start(process).map(Value::Text),
+        }
+    }
+}
+
+/// Parse a comma separated list of fields, like `pid,comm`.
+pub(crate) fn parse_fields(list: &str) -> Result<Vec<Field>, String> {
+    list.split(',')
+        .map(|name| {
+            Field::ALL
+                .into_iter()
+                .find(|field| field.name() == name)
+                .ok_or_else(|| {
+                    let names = Field::ALL.map(Field::name).join(", ");
+                    format!("unknown field '{name}', expected one of: {names}")
+                })
+        })
+        .collect()
+}
+
+/// The fields of the process separated by spaces, `-` standing for those that
+/// can't be read.
+pub(crate) fn format_text(process: &mut ProcessInformation, fields: &[Field]) -> String {
+    fields
+        .iter()
+        .map(|field| match field.value(process) {
+            Some(Value::Number(number)) => number.to_string(),
+            Some(Value::Text(text)) => text,
+            None => "-".into(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// An array with an object for each process, mapping the field names to their
+/// values, or to null for those that can't be read.
+pub(crate) fn format_json(processes: &mut [ProcessInformation], fields: &[Field]) -> String {
+    let objects = processes
+        .iter_mut()
+        .map(|process| {
+            let members = fields
+                .iter()
+                .map(|field| {
+                    let value = match field.value(process) {
+                        Some(Value::Number(number)) => number.to_string(),
+                        Some(Value::Text(text)) => json_string(&text),
+                        None => "null".into(),
+                    };
+                    format!("\"{}\":{value}", field.name())
+                })
+                .collect::<Vec<_>>();
+            format!("{{{}}}", members.join(","))
+        })
+        .collect::<Vec<_>>();
+    format!("[{}]", objects.join(","))
+}
+
+fn json_string(text: &str) -> String {
+    let mut result = String::from('"');
+    for c in text.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(result, "\\u{:04x}", c as u32);
+            }
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+/// The boot time, in seconds since the epoch
+#[cfg(target_os = "linux")]
+static BOOT_TIME: LazyLock<Option<i64>> = LazyLock::new(|| {
+    std::fs::read_to_string("/proc/stat")
+        .ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix("btime "))?
+        .trim()
+        .parse()
+        .ok()
+});
+
+/// When the process started, in ISO 8601 format and local time
+#[cfg(target_os = "linux")]
+fn start(process: &mut ProcessInformation) -> Option<String> {
+    use jiff::{tz::TimeZone, Timestamp};
+
+    let since_boot = (process.start_time().ok()? / crate::process_matcher::clock_ticks()) as i64;
+    let time = Timestamp::from_second((*BOOT_TIME)? + since_boot).ok()?;
+    Some(
+        time.to_zoned(TimeZone::system())
+            .strftime("%Y-%m-%dT%H:%M:%S%:z")
+            .to_string(),
+    )
+}
+
+#[cfg(not(target_os = "linux"))]
+fn start(_process: &mut ProcessInformation) -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_fields() {
+        assert_eq!(
+            parse_fields("pid,comm,args"),
+            Ok(vec![Field::Pid, Field::Comm, Field::Args])
+        );
+        assert_eq!(parse_fields("start"), Ok(vec![Field::Start]));
+        assert!(parse_fields("pid,").is_err());
+        assert!(parse_fields("pid,cmd").unwrap_err().contains("'cmd'"));
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("sh"), r#""sh""#);
+        assert_eq!(json_string("a \"b\"\\c\n\x01"), r#""a \"b\"\\c\n\u0001""#);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_format() {
+        let mut process = ProcessInformation::current_process_info().unwrap();
+        let pid = process.pid;
+        let ppid = process.ppid().unwrap();
+        let name = process.name().unwrap();
+
+        assert_eq!(
+            format_text(&mut process, &[Field::Pid, Field::Ppid, Field::Comm]),
+            format!("{pid} {ppid} {name}")
+        );
+        assert_eq!(
+            format_json(&mut [process], &[Field::Pid, Field::Comm]),
+            format!(r#"[{{"pid":{pid},"comm":"{name}"}}]"#)
+        );
+        assert_eq!(format_json(&mut [], &[Field::Pid]), "[]");
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_start() {
+        let mut process = ProcessInformation::current_process_info().unwrap();
+        let start = start(&mut process).unwrap();
+        let start: jiff::Timestamp = start.parse().unwrap();
+        let age = jiff::Timestamp::now().duration_since(start);
+        // The test process started moments ago, up to the rounding to seconds
+        assert!(age.as_secs() >= -1 && age.as_secs() < 600, "{age:?}");
+    }
+}
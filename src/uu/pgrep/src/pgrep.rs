@@ -4,10 +4,13 @@
 // file that was distributed with this source code.
 
 // Pid utils
+mod follow;
+mod output;
 pub mod process;
 pub mod process_matcher;
 
 use clap::{arg, crate_version, Command};
+use output::Field;
 use uucore::error::UResult;
 
 /// # Conceptual model of `pgrep`
@@ -28,15 +31,34 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     let mut settings = process_matcher::get_match_settings(&matches)?;
     settings.threads = matches.get_flag("lightweight");
 
+    if matches.get_flag("follow") {
+        return follow::follow(&settings);
+    }
+
     // Collect pids
-    let pids = process_matcher::find_matching_pids(&settings)?;
+    let mut pids = process_matcher::find_matching_pids(&settings)?;
 
     // Processing output
+    let delimiter = matches.get_one::<String>("delimiter").unwrap();
     let output = if matches.get_flag("count") {
         format!("{}", pids.len())
-    } else {
-        let delimiter = matches.get_one::<String>("delimiter").unwrap();
+    } else if matches.get_flag("json") || matches.contains_id("output") {
+        let fields = match matches.get_one::<Vec<Field>>("output") {
+            Some(fields) => fields.clone(),
+            None if matches.get_flag("list-full") => vec![Field::Pid, Field::Args],
+            None if matches.get_flag("list-name") => vec![Field::Pid, Field::Comm],
+            None => vec![Field::Pid],
+        };
 
+        if matches.get_flag("json") {
+            output::format_json(&mut pids, &fields)
+        } else {
+            pids.iter_mut()
+                .map(|it| output::format_text(it, &fields))
+                .collect::<Vec<_>>()
+                .join(delimiter)
+        }
+    } else {
         let formatted: Vec<_> = if matches.get_flag("list-full") {
             pids.into_iter()
                 .map(|it| {
@@ -72,7 +94,7 @@ pub fn uu_app() -> Command {
     Command::new(uucore::util_name())
         .version(crate_version!())
         .about("look up, signal, or wait for processes based on name and other attributes")
-        .override_usage("pgrep [options] <pattern>")
+        .override_usage("pgrep [options] <pattern>...")
         .args_override_self(true)
         .args([
             arg!(-d     --delimiter <string>    "specify output delimiter")
@@ -81,6 +103,12 @@ pub fn uu_app() -> Command {
             arg!(-l     --"list-name"           "list PID and process name"),
             arg!(-a     --"list-full"           "list PID and full command line"),
             arg!(-w     --lightweight           "list all TID"),
+            arg!(--output <fields>              "list the given comma separated fields: pid, ppid, user, comm, args, start")
+                .value_parser(output::parse_fields)
+                .conflicts_with_all(["list-name", "list-full"]),
+            arg!(--json                         "list the processes as a JSON array of objects"),
+            arg!(--follow                       "keep running, listing +PID and name as processes match, and -PID as they exit")
+                .conflicts_with_all(["count", "newest", "oldest", "list-name", "list-full", "output", "json"]),
         ])
         .args(process_matcher::clap_args(
             "Name of the program to find the PID of",
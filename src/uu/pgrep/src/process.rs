@@ -8,6 +8,10 @@ use std::fs::read_link;
 use std::hash::Hash;
 #[cfg(target_os = "linux")]
 use std::ops::RangeInclusive;
+#[cfg(target_os = "linux")]
+use std::os::fd::{AsFd, BorrowedFd, OwnedFd};
+#[cfg(target_os = "linux")]
+use std::sync::Arc;
 use std::sync::{LazyLock, OnceLock};
 use std::{
     collections::HashMap,
@@ -436,6 +440,9 @@ pub struct ProcessInformation {
     cached_start_time: Option<u64>,
 
     thread_ids: OnceLock<Vec<usize>>,
+
+    #[cfg(target_os = "linux")]
+    pidfd: Option<Arc<OwnedFd>>,
 }
 
 impl ProcessInformation {
@@ -552,6 +559,25 @@ impl ProcessInformation {
         Ok(time)
     }
 
+    /// CPU time spent in user and kernel mode, in clock ticks
+    pub fn cpu_time(&mut self) -> Result<u64, io::Error> {
+        Ok(self.get_numeric_stat_field(13)? + self.get_numeric_stat_field(14)?)
+    }
+
+    pub fn num_threads(&mut self) -> Result<u64, io::Error> {
+        self.get_numeric_stat_field(19)
+    }
+
+    /// Virtual memory size, in bytes
+    pub fn vsize(&mut self) -> Result<u64, io::Error> {
+        self.get_numeric_stat_field(22)
+    }
+
+    /// Resident set size, in pages
+    pub fn rss(&mut self) -> Result<u64, io::Error> {
+        self.get_numeric_stat_field(23)
+    }
+
     pub fn ppid(&mut self) -> Result<u64, io::Error> {
         // the PPID is the fourth field in /proc/<PID>/stat
         // (https://www.kernel.org/doc/html/latest/filesystems/proc.html#id10)
@@ -650,6 +676,26 @@ impl ProcessInformation {
         read_link(format!("/proc/{}/root", self.pid))
     }
 
+    // Current working directory of the process
+    pub fn cwd(&mut self) -> Result<PathBuf, io::Error> {
+        read_link(format!("/proc/{}/cwd", self.pid))
+    }
+
+    /// Returns the path of the executable of the process, and whether the file
+    /// was deleted since the process started it, as happens when a package
+    /// upgrade replaces it.
+    pub fn exe(&mut self) -> Result<(PathBuf, bool), io::Error> {
+        let exe = read_link(format!("/proc/{}/exe", self.pid))?;
+        // The kernel marks the path of a file that is gone with this suffix
+        match exe
+            .to_str()
+            .and_then(|path| path.strip_suffix(" (deleted)"))
+        {
+            Some(path) => Ok((PathBuf::from(path), true)),
+            None => Ok((exe, false)),
+        }
+    }
+
     /// Returns cgroups (both v1 and v2) that the process belongs to.
     pub fn cgroups(&mut self) -> Result<Vec<CgroupMembership>, io::Error> {
         fs::read_to_string(format!("/proc/{}/cgroup", self.pid))?
@@ -726,6 +772,45 @@ impl ProcessInformation {
     pub fn namespaces(&self) -> Result<Namespace, io::Error> {
         Namespace::from_pid(self.pid)
     }
+
+    /// Pin the process with a pidfd, so that it can be signalled or waited for
+    /// without reaching another process that reused its PID in the meantime.
+    ///
+    /// Returns false if the process is gone, or if its PID was reused since
+    /// its information was read, which is told by the start time. When the
+    /// kernel doesn't support pidfds the process is left without one, other
+    /// failures are returned.
+    #[cfg(target_os = "linux")]
+    pub fn open_pidfd(&mut self) -> io::Result<bool> {
+        use rustix::io::Errno;
+        use rustix::process::{pidfd_open, Pid, PidfdFlags};
+
+        let Some(pid) = Pid::from_raw(self.pid as i32) else {
+            return Ok(false);
+        };
+        let pidfd = match pidfd_open(pid, PidfdFlags::empty()) {
+            Ok(pidfd) => pidfd,
+            Err(Errno::SRCH) => return Ok(false),
+            // Not supported by the kernel, before 5.3
+            Err(Errno::NOSYS) => return Ok(true),
+            Err(e) => return Err(e.into()),
+        };
+
+        // The pidfd refers to whatever process has the PID now: it is the one
+        // we read if it started at the same time.
+        let started = self.start_time().ok();
+        let now = Self::from_pid(self.pid).and_then(|mut now| now.start_time());
+        if now.ok() != started {
+            return Ok(false);
+        }
+        self.pidfd = Some(Arc::new(pidfd));
+        Ok(true)
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn pidfd(&self) -> Option<BorrowedFd<'_>> {
+        self.pidfd.as_deref().map(AsFd::as_fd)
+    }
 }
 impl TryFrom<DirEntry> for ProcessInformation {
     type Error = io::Error;
@@ -980,6 +1065,28 @@ unknown              /dev/tty        4 1-63 console"#;
         assert_eq!(pid_entry.root().unwrap(), PathBuf::from("/"));
     }
 
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_resources() {
+        let mut pid_entry = ProcessInformation::current_process_info().unwrap();
+
+        assert!(pid_entry.num_threads().unwrap() >= 1);
+        assert!(pid_entry.rss().unwrap() > 0);
+        assert!(pid_entry.vsize().unwrap() > pid_entry.rss().unwrap());
+        assert!(pid_entry.cpu_time().is_ok());
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_cwd_and_exe() {
+        let mut pid_entry = ProcessInformation::current_process_info().unwrap();
+        assert_eq!(pid_entry.cwd().unwrap(), std::env::current_dir().unwrap());
+        assert_eq!(
+            pid_entry.exe().unwrap(),
+            (std::env::current_exe().unwrap(), false)
+        );
+    }
+
     #[test]
     #[cfg(target_os = "linux")]
     fn test_cgroups() {
@@ -1012,6 +1119,20 @@ unknown              /dev/tty        4 1-63 console"#;
         assert!(namespaces.uts.is_some());
     }
 
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_open_pidfd() {
+        let mut pid_entry = ProcessInformation::current_process_info().unwrap();
+        assert!(pid_entry.open_pidfd().unwrap());
+        assert!(pid_entry.pidfd().is_some());
+
+        // As if the PID had been reused by another process since it was read
+        let mut pid_entry = ProcessInformation::current_process_info().unwrap();
+        pid_entry.cached_start_time = Some(pid_entry.start_time().unwrap() + 1);
+        assert!(!pid_entry.open_pidfd().unwrap());
+        assert!(pid_entry.pidfd().is_none());
+    }
+
     #[test]
     #[cfg(target_os = "linux")]
     fn test_environ() {
@@ -6,10 +6,11 @@
 // Common process matcher logic shared by pgrep, pkill and pidwait
 
 use std::hash::Hash;
-use std::{collections::HashSet, io};
+use std::path::PathBuf;
+use std::{collections::HashSet, fs, io};
 
 use clap::{arg, Arg, ArgAction, ArgMatches};
-use regex::Regex;
+use regex::{Regex, RegexSet};
 #[cfg(unix)]
 use uucore::{
     display::Quotable,
@@ -18,11 +19,12 @@ use uucore::{
 };
 
 use uucore::error::{UResult, USimpleError};
+use uucore::parser::parse_size::parse_size_u64;
 
 use crate::process::{walk_process, walk_threads, Namespace, ProcessInformation, Teletype};
 
 pub struct Settings {
-    pub regex: Regex,
+    pub regex: RegexSet,
 
     pub exact: bool,
     pub full: bool,
@@ -31,6 +33,11 @@ pub struct Settings {
     pub newest: bool,
     pub oldest: bool,
     pub older: Option<u64>,
+    pub younger: Option<u64>,
+    pub rss_above: Option<u64>,
+    pub vsz_above: Option<u64>,
+    pub cpu_time_above: Option<u64>,
+    pub threads_above: Option<u64>,
     pub parent: Option<HashSet<u64>>,
     pub runstates: Option<String>,
     pub terminal: Option<HashSet<Teletype>>,
@@ -43,6 +50,12 @@ pub struct Settings {
     pub pgroup: Option<HashSet<u64>>,
     pub session: Option<HashSet<u64>>,
     pub cgroup: Option<HashSet<String>>,
+    pub cgroup_prefix: Option<Vec<String>>,
+    pub container: Option<Vec<String>>,
+    pub exe: Option<HashSet<PathBuf>>,
+    pub deleted: bool,
+    pub cwd: Option<HashSet<PathBuf>>,
+    pub root: Option<HashSet<PathBuf>>,
     pub namespaces: Option<Namespace>,
     pub env: Option<HashSet<String>>,
     pub threads: bool,
@@ -53,8 +66,10 @@ pub struct Settings {
 }
 
 pub fn get_match_settings(matches: &ArgMatches) -> UResult<Settings> {
-    let pattern = try_get_pattern_from(matches)?;
-    let regex = Regex::new(&pattern).map_err(|e| USimpleError::new(2, e.to_string()))?;
+    let patterns = try_get_patterns_from(matches)?;
+    // Without any pattern everything matches, unlike with an empty pattern file
+    let regex = RegexSet::new(patterns.as_deref().unwrap_or(&[String::new()]))
+        .map_err(|e| USimpleError::new(2, e.to_string()))?;
 
     let settings = Settings {
         regex,
@@ -69,6 +84,11 @@ pub fn get_match_settings(matches: &ArgMatches) -> UResult<Settings> {
             .map(|parents| parents.copied().collect()),
         runstates: matches.get_one::<String>("runstates").cloned(),
         older: matches.get_one::<u64>("older").copied(),
+        younger: matches.get_one::<u64>("younger").copied(),
+        rss_above: matches.get_one::<u64>("rss-above").copied(),
+        vsz_above: matches.get_one::<u64>("vsz-above").copied(),
+        cpu_time_above: matches.get_one::<u64>("cpu-time-above").copied(),
+        threads_above: matches.get_one::<u64>("threads-above").copied(),
         terminal: matches.get_many::<String>("terminal").map(|ttys| {
             ttys.cloned()
                 .flat_map(Teletype::try_from)
@@ -97,6 +117,16 @@ pub fn get_match_settings(matches: &ArgMatches) -> UResult<Settings> {
         cgroup: matches
             .get_many::<String>("cgroup")
             .map(|groups| groups.cloned().collect()),
+        cgroup_prefix: matches
+            .get_many::<String>("cgroup-prefix")
+            .map(|prefixes| prefixes.cloned().collect()),
+        container: matches
+            .get_many::<String>("container")
+            .map(|ids| ids.cloned().collect()),
+        exe: canonical_paths(matches, "exe"),
+        deleted: matches.get_flag("deleted"),
+        cwd: canonical_paths(matches, "cwd"),
+        root: canonical_paths(matches, "root"),
         namespaces: matches
             .get_one::<usize>("ns")
             .map(|pid| {
@@ -121,6 +151,11 @@ pub fn get_match_settings(matches: &ArgMatches) -> UResult<Settings> {
         && !settings.oldest
         && settings.runstates.is_none()
         && settings.older.is_none()
+        && settings.younger.is_none()
+        && settings.rss_above.is_none()
+        && settings.vsz_above.is_none()
+        && settings.cpu_time_above.is_none()
+        && settings.threads_above.is_none()
         && settings.parent.is_none()
         && settings.terminal.is_none()
         && settings.uid.is_none()
@@ -129,11 +164,17 @@ pub fn get_match_settings(matches: &ArgMatches) -> UResult<Settings> {
         && settings.pgroup.is_none()
         && settings.session.is_none()
         && settings.cgroup.is_none()
+        && settings.cgroup_prefix.is_none()
+        && settings.container.is_none()
+        && settings.exe.is_none()
+        && !settings.deleted
+        && settings.cwd.is_none()
+        && settings.root.is_none()
         && settings.namespaces.is_none()
         && settings.env.is_none()
         && !settings.require_handler
         && settings.pidfile.is_none()
-        && pattern.is_empty()
+        && patterns.is_none()
     {
         return Err(USimpleError::new(
             2,
@@ -151,17 +192,16 @@ pub fn get_match_settings(matches: &ArgMatches) -> UResult<Settings> {
 pub fn find_matching_pids(settings: &Settings) -> UResult<Vec<ProcessInformation>> {
     let mut pids = collect_matched_pids(settings)?;
 
-    let is_long_match = if settings.exact {
-        settings
-            .regex
-            .as_str()
-            .trim_matches('^')
-            .trim_matches('$')
-            .len()
-            > 15
-    } else {
-        settings.regex.as_str().len() > 15
-    };
+    // Then none of the patterns can match a process name
+    let patterns = settings.regex.patterns();
+    let is_long_match = !patterns.is_empty()
+        && patterns.iter().all(|pattern| {
+            if settings.exact {
+                pattern.trim_matches('^').trim_matches('$').len() > 15
+            } else {
+                pattern.len() > 15
+            }
+        });
 
     if pids.is_empty() {
         if !settings.full && is_long_match {
@@ -177,42 +217,168 @@ pub fn find_matching_pids(settings: &Settings) -> UResult<Vec<ProcessInformation
     }
 }
 
-/// Try to get the pattern from the command line arguments. Returns an empty string if no pattern
-/// is specified.
-fn try_get_pattern_from(matches: &ArgMatches) -> UResult<String> {
-    let pattern = match matches.get_many::<String>("pattern") {
-        Some(patterns) if patterns.len() > 1 => {
-            return Err(USimpleError::new(
-                2,
-                format!(
-                    "only one pattern can be provided\nTry `{} --help' for more information.",
-                    uucore::util_name()
-                ),
-            ))
+/// Open a pidfd for each of the processes, leaving out those that are gone by
+/// now, so that the ones signalled or waited for are the ones that matched.
+///
+/// A process whose pidfd can't be opened is kept, with a warning, and is then
+/// handled by its PID.
+#[cfg(target_os = "linux")]
+pub fn open_pidfds(pids: &mut Vec<ProcessInformation>) {
+    pids.retain_mut(|pid| match pid.open_pidfd() {
+        Ok(running) => running,
+        Err(e) => {
+            uucore::show_warning!(
+                "cannot open pidfd for pid {}: {}",
+                pid.pid,
+                uucore::error::strip_errno(&e)
+            );
+            true
         }
-        Some(mut patterns) => patterns.next().unwrap(),
-        None => return Ok(String::new()),
-    };
+    });
+}
 
-    let pattern = if matches.get_flag("ignore-case") {
-        &pattern.to_lowercase()
-    } else {
-        pattern
-    };
+#[cfg(not(target_os = "linux"))]
+pub fn open_pidfds(_pids: &mut Vec<ProcessInformation>) {}
+
+/// Try to get the patterns from the command line arguments and the pattern
+/// files, ready to be compiled. Returns None if no pattern is specified.
+fn try_get_patterns_from(matches: &ArgMatches) -> UResult<Option<Vec<String>>> {
+    let files = matches.get_many::<String>("pattern-file");
+    if !matches.contains_id("pattern") && files.is_none() {
+        return Ok(None);
+    }
 
-    let pattern = if matches.get_flag("exact") {
-        &format!("^{pattern}$")
-    } else {
-        pattern
-    };
+    let mut patterns: Vec<String> = matches
+        .get_many::<String>("pattern")
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect();
+    for filename in files.into_iter().flatten() {
+        let content = fs::read_to_string(filename).map_err(|e| {
+            USimpleError::new(2, format!("Failed to read pattern file {filename}: {e}"))
+        })?;
+        // One per line, leaving out blank lines, which would match everything
+        patterns.extend(
+            content
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(String::from),
+        );
+    }
+
+    let patterns = patterns
+        .into_iter()
+        .map(|pattern| {
+            let pattern = if matches.get_flag("fixed-strings") {
+                regex::escape(&pattern)
+            } else {
+                pattern
+            };
+
+            let pattern = if matches.get_flag("ignore-case") {
+                pattern.to_lowercase()
+            } else {
+                pattern
+            };
+
+            if matches.get_flag("exact") {
+                format!("^{pattern}$")
+            } else {
+                pattern
+            }
+        })
+        .collect();
 
-    Ok(pattern.clone())
+    Ok(Some(patterns))
 }
 
 fn any_matches<T: Eq + Hash>(optional_ids: &Option<HashSet<T>>, id: T) -> bool {
     optional_ids.as_ref().is_none_or(|ids| ids.contains(&id))
 }
 
+/// The paths given for the option, resolved like the links in `/proc/<pid>`
+/// are. Those that can't be, like the path of a deleted executable, are kept
+/// as they are.
+fn canonical_paths(matches: &ArgMatches, id: &str) -> Option<HashSet<PathBuf>> {
+    matches.get_many::<PathBuf>(id).map(|paths| {
+        paths
+            .map(|path| fs::canonicalize(path).unwrap_or_else(|_| path.clone()))
+            .collect()
+    })
+}
+
+/// Whether the cgroup is `prefix` or one below it, like `/system.slice/cron.service`
+/// for `/system.slice`.
+fn cgroup_has_prefix(cgroup: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim_end_matches('/');
+    cgroup
+        .strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Whether the cgroup belongs to the container with the given ID, or the start
+/// of it like `docker ps` shows. Container runtimes name a cgroup after the
+/// full ID, like `/system.slice/docker-<id>.scope` or `/kubepods/.../<id>`.
+fn cgroup_in_container(cgroup: &str, id: &str) -> bool {
+    cgroup.split('/').any(|component| {
+        let component = component.strip_suffix(".scope").unwrap_or(component);
+        let full_id = component
+            .rsplit_once('-')
+            .map_or(component, |(_, full_id)| full_id);
+        full_id.len() == 64
+            && full_id.chars().all(|c| c.is_ascii_hexdigit())
+            && full_id.starts_with(id)
+    })
+}
+
+fn parse_container_id(id: &str) -> Result<String, String> {
+    if !id.is_empty() && id.len() <= 64 && id.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(id.to_lowercase())
+    } else {
+        Err(format!("invalid container ID '{id}'"))
+    }
+}
+
+fn above(limit: Option<u64>, value: io::Result<u64>) -> bool {
+    limit.is_none_or(|limit| value.is_ok_and(|value| value > limit))
+}
+
+/// Time since boot, in whole seconds
+fn uptime() -> Option<u64> {
+    let uptime = fs::read_to_string("/proc/uptime").ok()?;
+    let seconds = uptime.split_once('.')?.0;
+    seconds.parse().ok()
+}
+
+/// Clock ticks per second, the unit of the times in `/proc`
+pub(crate) fn clock_ticks() -> u64 {
+    #[cfg(unix)]
+    {
+        rustix::param::clock_ticks_per_second()
+    }
+    #[cfg(not(unix))]
+    {
+        100
+    }
+}
+
+/// Bytes per page, the unit of the memory sizes in `/proc`
+pub(crate) fn page_size() -> u64 {
+    #[cfg(unix)]
+    {
+        rustix::param::page_size() as u64
+    }
+    #[cfg(not(unix))]
+    {
+        4096
+    }
+}
+
+fn parse_size(size: &str) -> Result<u64, String> {
+    parse_size_u64(size).map_err(|e| e.to_string())
+}
+
 fn get_ancestors(process_infos: &mut [ProcessInformation], mut pid: usize) -> HashSet<usize> {
     let mut ret = HashSet::from([pid]);
     while pid != 1 {
@@ -243,16 +409,37 @@ fn get_namespaces(_pid: usize, _list: Option<Vec<&str>>) -> UResult<Namespace> {
 }
 
 /// Collect pids with filter construct from command line arguments
+/// Of the processes, or threads, with the given IDs, those that match, like
+/// [find_matching_pids] finds them but without `--newest` and `--oldest`.
+#[cfg(target_os = "linux")]
+pub(crate) fn find_matching_among(
+    settings: &Settings,
+    ids: &[usize],
+) -> UResult<Vec<ProcessInformation>> {
+    let pids = ids
+        .iter()
+        .filter_map(|&id| ProcessInformation::from_pid(id).ok())
+        .collect();
+    filter_matching(settings, pids)
+}
+
 fn collect_matched_pids(settings: &Settings) -> UResult<Vec<ProcessInformation>> {
+    let pids = if settings.threads {
+        walk_threads().collect::<Vec<_>>()
+    } else {
+        walk_process().collect::<Vec<_>>()
+    };
+    filter_matching(settings, pids)
+}
+
+fn filter_matching(
+    settings: &Settings,
+    mut pids: Vec<ProcessInformation>,
+) -> UResult<Vec<ProcessInformation>> {
     // Filtration general parameters
     let filtered: Vec<ProcessInformation> = {
         let mut tmp_vec = Vec::new();
 
-        let mut pids = if settings.threads {
-            walk_threads().collect::<Vec<_>>()
-        } else {
-            walk_process().collect::<Vec<_>>()
-        };
         let our_pid = std::process::id() as usize;
         let ignored_pids = if settings.ignore_ancestors {
             get_ancestors(&mut pids, our_pid)
@@ -260,6 +447,9 @@ fn collect_matched_pids(settings: &Settings) -> UResult<Vec<ProcessInformation>>
             HashSet::from([our_pid])
         };
 
+        let uptime = uptime();
+        let clock_ticks = clock_ticks();
+
         let pid_from_pidfile = settings
             .pidfile
             .as_ref()
@@ -299,8 +489,28 @@ fn collect_matched_pids(settings: &Settings) -> UResult<Vec<ProcessInformation>>
 
             let tty_matched = any_matches(&settings.terminal, pid.tty());
 
-            let arg_older = settings.older.unwrap_or(0);
-            let older_matched = pid.start_time().unwrap() >= arg_older;
+            // In whole seconds, like the limits
+            let age = uptime
+                .zip(pid.start_time().ok())
+                .map(|(uptime, start_time)| uptime.saturating_sub(start_time / clock_ticks));
+            let older_matched = settings
+                .older
+                .is_none_or(|older| age.is_some_and(|age| age >= older));
+            let younger_matched = settings
+                .younger
+                .is_none_or(|younger| age.is_some_and(|age| age < younger));
+
+            let resources_matched = above(
+                settings.rss_above,
+                pid.rss().map(|pages| pages * page_size()),
+            ) && above(settings.vsz_above, pid.vsize())
+                && above(
+                    settings
+                        .cpu_time_above
+                        .map(|seconds| seconds.saturating_mul(clock_ticks)),
+                    pid.cpu_time(),
+                )
+                && above(settings.threads_above, pid.num_threads());
 
             let parent_matched = any_matches(&settings.parent, pid.ppid().unwrap());
             let pgroup_matched = any_matches(&settings.pgroup, pid.pgid().unwrap());
@@ -309,6 +519,29 @@ fn collect_matched_pids(settings: &Settings) -> UResult<Vec<ProcessInformation>>
                 &settings.cgroup,
                 pid.cgroup_v2_path().unwrap_or("/".to_string()),
             );
+            let cgroup_prefix_matched = settings.cgroup_prefix.as_ref().is_none_or(|prefixes| {
+                pid.cgroup_v2_path().is_ok_and(|path| {
+                    prefixes
+                        .iter()
+                        .any(|prefix| cgroup_has_prefix(&path, prefix))
+                })
+            });
+            let container_matched = settings.container.as_ref().is_none_or(|ids| {
+                pid.cgroup_v2_path()
+                    .is_ok_and(|path| ids.iter().any(|id| cgroup_in_container(&path, id)))
+            });
+
+            let exe_matched = (settings.exe.is_none() && !settings.deleted)
+                || pid.exe().is_ok_and(|(exe, deleted)| {
+                    any_matches(&settings.exe, exe) && (deleted || !settings.deleted)
+                });
+            let cwd_matched = settings.cwd.is_none()
+                || pid.cwd().is_ok_and(|cwd| any_matches(&settings.cwd, cwd));
+            let root_matched = settings.root.is_none()
+                || pid
+                    .root()
+                    .is_ok_and(|root| any_matches(&settings.root, root));
+
             let namespace_matched = settings
                 .namespaces
                 .as_ref()
@@ -357,10 +590,17 @@ fn collect_matched_pids(settings: &Settings) -> UResult<Vec<ProcessInformation>>
                 && pattern_matched
                 && tty_matched
                 && older_matched
+                && younger_matched
+                && resources_matched
                 && parent_matched
                 && pgroup_matched
                 && session_matched
                 && cgroup_matched
+                && cgroup_prefix_matched
+                && container_matched
+                && exe_matched
+                && cwd_matched
+                && root_matched
                 && namespace_matched
                 && env_matched
                 && ids_matched
@@ -419,9 +659,43 @@ fn process_flag_o_n(
 #[cfg(unix)]
 fn parse_signal_value(signal_name: &str) -> UResult<usize> {
     signal_by_name_or_value(signal_name)
+        .or_else(|| realtime_signal(signal_name))
         .ok_or_else(|| USimpleError::new(1, format!("Unknown signal {}", signal_name.quote())))
 }
 
+/// A real-time signal, either as a number or as `RTMIN`, `RTMIN+n`, `RTMAX-n`
+/// or `RTMAX`. Only the range left by libc is accepted, as it reserves the first
+/// few real-time signals for itself.
+#[cfg(target_os = "linux")]
+pub fn realtime_signal(signal_name: &str) -> Option<usize> {
+    use uucore::libc::{SIGRTMAX, SIGRTMIN};
+
+    let (min, max) = (SIGRTMIN() as usize, SIGRTMAX() as usize);
+    let name = signal_name.to_uppercase();
+    let name = name.trim_start_matches("SIG");
+    let signal = if let Ok(value) = name.parse() {
+        value
+    } else if let Some(offset) = name.strip_prefix("RTMIN") {
+        match offset {
+            "" => min,
+            _ => min.checked_add(offset.strip_prefix('+')?.parse().ok()?)?,
+        }
+    } else if let Some(offset) = name.strip_prefix("RTMAX") {
+        match offset {
+            "" => max,
+            _ => max.checked_sub(offset.strip_prefix('-')?.parse().ok()?)?,
+        }
+    } else {
+        return None;
+    };
+    (min..=max).contains(&signal).then_some(signal)
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+pub fn realtime_signal(_signal_name: &str) -> Option<usize> {
+    None
+}
+
 #[cfg(not(unix))]
 pub fn usr2uid(_name: &str) -> io::Result<u32> {
     Err(io::Error::new(
@@ -498,6 +772,85 @@ fn test_parse_pidfile_content_valid() {
     assert_eq!(parse_pidfile_content("\n123\n"), None);
 }
 
+#[cfg(target_os = "linux")]
+#[test]
+fn test_realtime_signal() {
+    let (min, max) = (
+        uucore::libc::SIGRTMIN() as usize,
+        uucore::libc::SIGRTMAX() as usize,
+    );
+    assert_eq!(realtime_signal("RTMIN"), Some(min));
+    assert_eq!(realtime_signal("sigrtmin+2"), Some(min + 2));
+    assert_eq!(realtime_signal("RTMAX-1"), Some(max - 1));
+    assert_eq!(realtime_signal("SIGRTMAX"), Some(max));
+    assert_eq!(realtime_signal(&min.to_string()), Some(min));
+
+    assert_eq!(realtime_signal("RTMIN-1"), None);
+    assert_eq!(realtime_signal("RTMAX+1"), None);
+    assert_eq!(realtime_signal(&(max + 1).to_string()), None);
+    assert_eq!(realtime_signal(&(min - 1).to_string()), None);
+    assert_eq!(realtime_signal("RTMINX"), None);
+    assert_eq!(realtime_signal("USR1"), None);
+}
+
+#[test]
+fn test_cgroup_has_prefix() {
+    assert!(cgroup_has_prefix(
+        "/system.slice/cron.service",
+        "/system.slice"
+    ));
+    assert!(cgroup_has_prefix(
+        "/system.slice/cron.service",
+        "/system.slice/"
+    ));
+    assert!(cgroup_has_prefix("/system.slice", "/system.slice"));
+    assert!(cgroup_has_prefix("/init.scope", "/"));
+    assert!(!cgroup_has_prefix("/system.slices", "/system.slice"));
+    assert!(!cgroup_has_prefix("/user.slice", "/system.slice"));
+}
+
+#[test]
+fn test_cgroup_in_container() {
+    let id = "4f1c5a6d9e2b7c8a0f3e6d1b2a9c8e7f6d5c4b3a2918f7e6d5c4b3a29180f7e6";
+    for cgroup in [
+        format!("/system.slice/docker-{id}.scope"),
+        format!("/machine.slice/libpod-{id}.scope/container"),
+        format!("/kubepods/burstable/pod1234/{id}"),
+        format!("/kubepods.slice/cri-containerd-{id}.scope"),
+    ] {
+        assert!(cgroup_in_container(&cgroup, id), "{cgroup}");
+        assert!(cgroup_in_container(&cgroup, &id[..12]), "{cgroup}");
+        assert!(!cgroup_in_container(&cgroup, "4f1c5a6d9e2c"), "{cgroup}");
+    }
+    assert!(!cgroup_in_container("/user.slice/session-3.scope", "3"));
+    assert!(!cgroup_in_container("/", "4f1c"));
+}
+
+#[test]
+fn test_parse_container_id() {
+    assert_eq!(parse_container_id("4F1c5a"), Ok("4f1c5a".to_string()));
+    assert!(parse_container_id("").is_err());
+    assert!(parse_container_id("docker-4f1c").is_err());
+}
+
+#[test]
+fn test_try_get_patterns_from() {
+    let patterns = |args: &[&str]| {
+        let command = clap::Command::new("pgrep").args(clap_args("", true));
+        try_get_patterns_from(&command.get_matches_from(args)).unwrap()
+    };
+
+    assert_eq!(patterns(&["pgrep"]), None);
+    assert_eq!(
+        patterns(&["pgrep", "-i", "Sh", "b.sh"]),
+        Some(vec!["sh".into(), "b.sh".into()])
+    );
+    assert_eq!(
+        patterns(&["pgrep", "-x", "--fixed-strings", "a.b", "c+"]),
+        Some(vec![r"^a\.b$".into(), r"^c\+$".into()])
+    );
+}
+
 #[cfg(unix)]
 fn is_locked(file: &std::fs::File) -> bool {
     use rustix::fs::FlockOperation;
@@ -573,6 +926,16 @@ pub fn clap_args(pattern_help: &'static str, enable_v_flag: bool) -> Vec<Arg> {
             .group("oldest_newest_inverse"),
         arg!(-O --older <seconds>      "select where older than seconds")
             .value_parser(clap::value_parser!(u64)),
+        arg!(--younger <seconds>       "select where younger than seconds")
+            .value_parser(clap::value_parser!(u64)),
+        arg!(--"rss-above" <size>      "select where the resident memory is above size, like 8G")
+            .value_parser(parse_size),
+        arg!(--"vsz-above" <size>      "select where the virtual memory is above size")
+            .value_parser(parse_size),
+        arg!(--"cpu-time-above" <seconds> "select where the CPU time is above seconds")
+            .value_parser(clap::value_parser!(u64)),
+        arg!(--"threads-above" <N>     "select where the number of threads is above N")
+            .value_parser(clap::value_parser!(u64)),
         arg!(-P --parent <PPID>        "match only child processes of the given parent")
             .value_delimiter(',')
             .value_parser(clap::value_parser!(u64)),
@@ -589,11 +952,29 @@ pub fn clap_args(pattern_help: &'static str, enable_v_flag: bool) -> Vec<Arg> {
             .value_delimiter(',')
             .value_parser(parse_uid_or_username),
         arg!(-x --exact                "match exactly with the command name"),
+        arg!(--"pattern-file" <file>   "read patterns from file, one per line")
+            .action(ArgAction::Append),
+        arg!(--"fixed-strings"         "match the patterns as plain strings, not regexes"),
         arg!(-F --pidfile <file>       "read PIDs from file"),
         arg!(-L --logpidfile           "fail if PID file is not locked"),
         arg!(-r --runstates <state>    "match runstates [D,S,Z,...]"),
         arg!(-A --"ignore-ancestors"   "exclude our ancestors from results"),
         arg!(--cgroup <grp>            "match by cgroup v2 names").value_delimiter(','),
+        arg!(--"cgroup-prefix" <path>  "match by cgroup v2 path, including the cgroups below it")
+            .value_delimiter(','),
+        arg!(--container <ID>          "match processes of the containers with the given IDs")
+            .value_delimiter(',')
+            .value_parser(parse_container_id),
+        arg!(--exe <path>              "match by executable")
+            .action(ArgAction::Append)
+            .value_parser(clap::value_parser!(PathBuf)),
+        arg!(--deleted                 "match only if the executable was deleted or replaced"),
+        arg!(--cwd <dir>               "match by current working directory")
+            .action(ArgAction::Append)
+            .value_parser(clap::value_parser!(PathBuf)),
+        arg!(--root <dir>              "match by root directory")
+            .action(ArgAction::Append)
+            .value_parser(clap::value_parser!(PathBuf)),
         arg!(--env <"name[=val],...">      "match on environment variable").value_delimiter(','),
         arg!(--ns <PID>                "match the processes that belong to the same namespace as <pid>")
             .value_parser(clap::value_parser!(usize)),
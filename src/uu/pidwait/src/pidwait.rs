@@ -4,11 +4,16 @@
 // file that was distributed with this source code.
 
 use clap::{arg, crate_version, Command};
+use std::time::{Duration, Instant};
 use uu_pgrep::process_matcher;
-use uucore::error::UResult;
+use uucore::error::{FromIo, UResult};
+use uucore::parser::parse_time;
 use wait::wait;
 
-mod wait;
+/// Exit code when processes are still running after `--timeout`
+const TIMEOUT_EXIT_CODE: i32 = 124;
+
+pub mod wait;
 
 #[uucore::main]
 pub fn uumain(args: impl uucore::Args) -> UResult<()> {
@@ -16,6 +21,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
 
     let settings = process_matcher::get_match_settings(&matches)?;
     let mut proc_infos = process_matcher::find_matching_pids(&settings)?;
+    process_matcher::open_pidfds(&mut proc_infos);
 
     // For empty result
     if proc_infos.is_empty() {
@@ -39,18 +45,43 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         }
     }
 
-    wait(&proc_infos);
+    let echo = matches.get_flag("echo");
+    let any = matches.get_flag("any");
+    let timeout = matches.get_one::<Duration>("timeout").copied();
+
+    let start = Instant::now();
+    let mut exited = 0;
+    let running = wait(&proc_infos, timeout, any, |proc| {
+        exited += 1;
+        if echo {
+            let name = proc.clone().name().unwrap_or_default();
+            let elapsed = start.elapsed().as_secs_f64();
+            println!("{name} (pid {}) exited after {elapsed:.2}s", proc.pid);
+        }
+    })
+    .map_err_context(|| "waiting failed".into())?;
+
+    // With --any, a single process exiting is enough
+    if !running.is_empty() && (!any || exited == 0) {
+        uucore::error::set_exit_code(TIMEOUT_EXIT_CODE);
+    }
 
     Ok(())
 }
 
+#[allow(clippy::cognitive_complexity)]
 pub fn uu_app() -> Command {
     Command::new(uucore::util_name())
         .version(crate_version!())
         .about("Wait for processes based on name")
-        .override_usage("pidwait [options] pattern")
+        .override_usage("pidwait [options] pattern...")
         .infer_long_args(true)
-        .args([arg!(-e --echo                      "display PIDs before waiting")])
+        .args([
+            arg!(-e --echo                      "display PIDs before waiting, and as they exit"),
+            arg!(--any                          "return as soon as one of the processes exits"),
+            arg!(--timeout <duration>           "give up waiting after duration, exiting with 124")
+                .value_parser(|duration: &str| parse_time::from_str(duration, true)),
+        ])
         .args(process_matcher::clap_args(
             "Name of the program to wait for",
             true,
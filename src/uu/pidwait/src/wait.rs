@@ -3,50 +3,149 @@
 // For the full copyright and license information, please view the LICENSE
 // file that was distributed with this source code.
 
+use std::io;
+use std::time::{Duration, Instant};
 use uu_pgrep::process::ProcessInformation;
 
+/// How often the processes without a pidfd are checked
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Wait for the processes to exit, or with `any` for one of them, for at most
+/// `timeout`. `on_exit` is called for each process as it exits. How it ended
+/// isn't known: the processes waited for are not our children.
+///
+/// Processes are waited for with their pidfd when they have one, and are
+/// otherwise checked every [POLL_INTERVAL].
+///
+/// Returns the processes still running then.
+pub fn wait(
+    procs: &[ProcessInformation],
+    timeout: Option<Duration>,
+    any: bool,
+    mut on_exit: impl FnMut(&ProcessInformation),
+) -> io::Result<Vec<&ProcessInformation>> {
+    let deadline = timeout.and_then(|timeout| Instant::now().checked_add(timeout));
+    let mut running: Vec<&ProcessInformation> = procs.iter().collect();
+
+    while !running.is_empty() {
+        let left = deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+        if left.is_some_and(|left| left.is_zero()) {
+            break;
+        }
+        let timeout = if running.iter().all(|proc| has_pidfd(proc)) {
+            left
+        } else {
+            Some(left.map_or(POLL_INTERVAL, |left| left.min(POLL_INTERVAL)))
+        };
+
+        let mut exited = wait_pidfds(&running, timeout)?;
+        exited.extend(
+            running
+                .iter()
+                .enumerate()
+                .filter(|(_, proc)| !has_pidfd(proc) && !is_running(proc))
+                .map(|(i, _)| i),
+        );
+        exited.sort_unstable();
+
+        for &i in exited.iter().rev() {
+            on_exit(running.remove(i));
+        }
+        if any && !exited.is_empty() {
+            break;
+        }
+    }
+
+    Ok(running)
+}
+
 #[cfg(target_os = "linux")]
-pub(crate) fn wait(procs: &[ProcessInformation]) {
-    use std::os::fd::OwnedFd;
+fn has_pidfd(proc: &ProcessInformation) -> bool {
+    proc.pidfd().is_some()
+}
 
-    use rustix::event::{poll, PollFd, PollFlags};
-    use rustix::process::{pidfd_open, Pid, PidfdFlags};
+#[cfg(not(target_os = "linux"))]
+fn has_pidfd(_proc: &ProcessInformation) -> bool {
+    false
+}
 
-    let mut pidfds: Vec<OwnedFd> = procs
+/// Wait for at most `timeout` for the processes with a pidfd, returning the
+/// indices of those that exited. None did when interrupted by a signal.
+#[cfg(target_os = "linux")]
+fn wait_pidfds(
+    running: &[&ProcessInformation],
+    timeout: Option<Duration>,
+) -> io::Result<Vec<usize>> {
+    use rustix::event::{poll, PollFd, PollFlags, Timespec};
+    use rustix::io::Errno;
+
+    let (indices, mut fds): (Vec<usize>, Vec<PollFd>) = running
         .iter()
-        .filter_map(|proc| {
-            let pid = Pid::from_raw(proc.pid as i32)?;
-            pidfd_open(pid, PidfdFlags::empty()).ok()
-        })
-        .collect();
+        .enumerate()
+        .filter_map(|(i, proc)| Some((i, PollFd::from_borrowed_fd(proc.pidfd()?, PollFlags::IN))))
+        .unzip();
+    let timeout = timeout.and_then(|timeout| Timespec::try_from(timeout).ok());
+    match poll(&mut fds, timeout.as_ref()) {
+        Ok(_) => {}
+        Err(Errno::INTR) => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    }
 
-    while !pidfds.is_empty() {
-        let to_remove = {
-            let mut fds: Vec<PollFd> = pidfds
-                .iter()
-                .map(|fd| PollFd::new(fd, PollFlags::IN))
-                .collect();
+    Ok(indices
+        .into_iter()
+        .zip(fds)
+        .filter(|(_, pfd)| pfd.revents().contains(PollFlags::IN))
+        .map(|(i, _)| i)
+        .collect())
+}
 
-            if poll(&mut fds, None).is_err() {
-                break;
-            }
+#[cfg(not(target_os = "linux"))]
+fn wait_pidfds(
+    _running: &[&ProcessInformation],
+    timeout: Option<Duration>,
+) -> io::Result<Vec<usize>> {
+    std::thread::sleep(timeout.unwrap_or(POLL_INTERVAL));
+    Ok(Vec::new())
+}
 
-            fds.iter()
-                .enumerate()
-                .filter(|(_, pfd)| pfd.revents().contains(PollFlags::IN))
-                .map(|(i, _)| i)
-                .rev()
-                .collect::<Vec<_>>()
-        };
+/// Whether a process without a pidfd is still running: its PID must exist,
+/// and belong to a live process started at the same time as the one matched,
+/// and not to another that reused the PID.
+#[cfg(unix)]
+fn is_running(proc: &ProcessInformation) -> bool {
+    use rustix::io::Errno;
+    use rustix::process::{test_kill_process, Pid};
 
-        for i in to_remove {
-            pidfds.remove(i);
+    let Some(pid) = Pid::from_raw(proc.pid as i32) else {
+        return false;
+    };
+    // EPERM: the process exists, but belongs to someone else
+    if !matches!(test_kill_process(pid), Ok(()) | Err(Errno::PERM)) {
+        return false;
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use uu_pgrep::process::RunState;
+
+        let Ok(mut now) = ProcessInformation::from_pid(proc.pid) else {
+            return false;
+        };
+        if matches!(now.run_state(), Ok(RunState::Zombie | RunState::Dead)) {
+            return false;
+        }
+        // Without a start time there's nothing to compare, trust the PID
+        if let Ok(started) = proc.clone().start_time() {
+            return now.start_time().is_ok_and(|now| now == started);
         }
     }
+    true
 }
 
-#[cfg(not(target_os = "linux"))]
-pub(crate) fn wait(_procs: &[ProcessInformation]) {}
+#[cfg(not(unix))]
+fn is_running(_proc: &ProcessInformation) -> bool {
+    false
+}
 
 #[cfg(test)]
 mod tests {
@@ -63,9 +162,13 @@ mod tests {
         let mut child = Command::new("sleep").arg("1").spawn().unwrap();
         let pid = child.id() as usize;
 
-        let info = ProcessInformation::from_pid(pid).unwrap();
+        let mut info = ProcessInformation::from_pid(pid).unwrap();
+        assert!(info.open_pidfd().unwrap());
         let start = Instant::now();
-        wait(&[info]);
+        let mut exits = Vec::new();
+        assert!(wait(&[info], None, false, |proc| exits.push(proc.pid))
+            .unwrap()
+            .is_empty());
         let elapsed = start.elapsed();
 
         assert!(
@@ -77,6 +180,87 @@ mod tests {
             "wait took too long: {elapsed:?}"
         );
 
-        let _ = child.wait();
+        // Waited for, but left for its parent to reap
+        assert_eq!(exits, [pid]);
+        assert!(child.wait().unwrap().success());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_wait_any() {
+        use super::*;
+        use std::process::Command;
+
+        let mut children =
+            ["0.1", "5"].map(|time| Command::new("sleep").arg(time).spawn().unwrap());
+        let procs = children.each_ref().map(|child| {
+            let mut info = ProcessInformation::from_pid(child.id() as usize).unwrap();
+            assert!(info.open_pidfd().unwrap());
+            info
+        });
+
+        let mut exited = Vec::new();
+        let running = wait(&procs, None, true, |proc| exited.push(proc.pid)).unwrap();
+        assert_eq!(exited, [procs[0].pid]);
+        assert_eq!(running.len(), 1);
+        assert_eq!(running[0].pid, procs[1].pid);
+
+        children[1].kill().unwrap();
+        children[1].wait().unwrap();
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_wait_timeout() {
+        use super::*;
+        use std::process::Command;
+        use std::time::Instant;
+
+        let mut child = Command::new("sleep").arg("5").spawn().unwrap();
+        let mut info = ProcessInformation::from_pid(child.id() as usize).unwrap();
+        assert!(info.open_pidfd().unwrap());
+
+        let start = Instant::now();
+        let procs = [info];
+        let running = wait(&procs, Some(Duration::from_millis(200)), false, |_| {
+            panic!("no process exited")
+        })
+        .unwrap();
+        let elapsed = start.elapsed();
+
+        assert_eq!(running.len(), 1);
+        assert_eq!(running[0].pid, child.id() as usize);
+        assert!(
+            elapsed >= Duration::from_millis(200) && elapsed < Duration::from_secs(2),
+            "wait returned after {elapsed:?}"
+        );
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_wait_without_pidfd() {
+        use super::*;
+        use std::process::Command;
+
+        let mut child = Command::new("sleep").arg("0.3").spawn().unwrap();
+        let pid = child.id() as usize;
+        // As on kernels without pidfd_open
+        let info = ProcessInformation::from_pid(pid).unwrap();
+        assert!(info.pidfd().is_none());
+
+        let procs = [info];
+        let mut exits = Vec::new();
+        let running = wait(&procs, Some(Duration::from_secs(3)), false, |proc| {
+            exits.push(proc.pid);
+        })
+        .unwrap();
+        assert!(running.is_empty());
+
+        // Polled, and left for its parent to reap
+        assert_eq!(exits, [pid]);
+        assert!(child.wait().unwrap().success());
     }
 }
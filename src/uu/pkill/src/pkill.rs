@@ -13,9 +13,14 @@ use rustix::{
 #[cfg(unix)]
 use std::io::Error;
 #[cfg(unix)]
+use std::time::Duration;
+#[cfg(unix)]
 use uu_pgrep::process::ProcessInformation;
 use uu_pgrep::process_matcher;
+#[cfg(unix)]
+use uu_pidwait::wait::wait;
 use uucore::error::UResult;
+use uucore::parser::parse_time;
 #[cfg(unix)]
 use uucore::{
     error::FromIo,
@@ -44,13 +49,14 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         None
     } else {
         Some(
-            Signal::from_named_raw(settings.signal as i32)
+            to_signal(settings.signal)
                 .ok_or_else(|| Error::from_raw_os_error(Errno::INVAL.raw_os_error()))?,
         )
     };
 
     // Collect pids
-    let pids = process_matcher::find_matching_pids(&settings)?;
+    let mut pids = process_matcher::find_matching_pids(&settings)?;
+    process_matcher::open_pidfds(&mut pids);
 
     // Send signal
     #[cfg(unix)]
@@ -58,7 +64,22 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         let echo = matches.get_flag("echo");
         let queue = matches.get_one::<u32>("queue").cloned();
 
-        kill(&pids, sig, queue, echo);
+        let signalled = kill(&pids, sig, queue, echo);
+
+        if let Some(&timeout) = matches.get_one::<Duration>("kill-after") {
+            let survivors: Vec<_> = wait(&signalled, Some(timeout), false, |_| {})
+                .map_err_context(|| "waiting failed".into())?
+                .into_iter()
+                .cloned()
+                .collect();
+            for pid in kill(&survivors, Some(Signal::KILL), None, false) {
+                println!(
+                    "{} killed with SIGKILL (pid {})",
+                    command_name(&pid),
+                    pid.pid
+                );
+            }
+        }
     }
 
     if matches.get_flag("count") {
@@ -73,7 +94,8 @@ fn handle_obsolete(args: &mut [String]) {
     for arg in &mut args[1..] {
         if let Some(signal) = arg.strip_prefix('-') {
             // Check if it is a valid signal
-            let opt_signal = signal_by_name_or_value(signal);
+            let opt_signal = signal_by_name_or_value(signal)
+                .or_else(|| process_matcher::realtime_signal(signal));
             if opt_signal.is_some() {
                 // Replace with long option that clap can parse
                 *arg = format!("--signal={signal}");
@@ -82,29 +104,53 @@ fn handle_obsolete(args: &mut [String]) {
     }
 }
 
+#[cfg(unix)]
+fn to_signal(signal: usize) -> Option<Signal> {
+    let signal = signal as i32;
+    #[cfg(target_os = "linux")]
+    if (uucore::libc::SIGRTMIN()..=uucore::libc::SIGRTMAX()).contains(&signal) {
+        // Safety: real-time signals from SIGRTMIN on are not reserved by libc
+        return Some(unsafe { Signal::from_raw_unchecked(signal) });
+    }
+    Signal::from_named_raw(signal)
+}
+
+/// Returns the processes that were signalled.
 #[cfg(unix)]
 #[allow(unused_variables)]
-fn kill(pids: &Vec<ProcessInformation>, sig: Option<Signal>, queue: Option<u32>, echo: bool) {
+fn kill(
+    pids: &[ProcessInformation],
+    sig: Option<Signal>,
+    queue: Option<u32>,
+    echo: bool,
+) -> Vec<ProcessInformation> {
+    let mut signalled = Vec::new();
     for pid in pids {
         #[cfg(target_os = "linux")]
-        let result = if queue.is_some() {
-            Err(Errno::NOTSUP)
-        } else {
-            send_signal(pid.pid, sig)
-        };
+        let result = signal_process(pid, sig, queue);
         #[cfg(not(target_os = "linux"))]
         let result = send_signal(pid.pid, sig);
-        if let Err(e) = result {
-            show!(Error::from_raw_os_error(e.raw_os_error())
-                .map_err_context(|| format!("killing pid {} failed", pid.pid)));
-        } else if echo {
-            println!(
-                "{} killed (pid {})",
-                pid.cmdline.split(" ").next().unwrap_or(""),
-                pid.pid
-            );
+        match result {
+            Ok(()) => {}
+            // Gone by now, like the survivors that exit right at the --kill-after deadline
+            Err(Errno::SRCH) => continue,
+            Err(e) => {
+                show!(Error::from_raw_os_error(e.raw_os_error())
+                    .map_err_context(|| format!("killing pid {} failed", pid.pid)));
+                continue;
+            }
+        }
+        if echo {
+            println!("{} killed (pid {})", command_name(pid), pid.pid);
         }
+        signalled.push(pid.clone());
     }
+    signalled
+}
+
+#[cfg(unix)]
+fn command_name(pid: &ProcessInformation) -> &str {
+    pid.cmdline.split(" ").next().unwrap_or("")
 }
 
 #[cfg(unix)]
@@ -117,21 +163,144 @@ fn send_signal(pid: usize, sig: Option<Signal>) -> Result<(), Errno> {
     }
 }
 
+/// Signal the process through its pidfd when it has one, so that the signal
+/// can't reach another process that reused the PID.
+///
+/// With a `queue` value, the signal is sent like `sigqueue(3)` does, and the
+/// process gets the value in the `si_value` of its `siginfo_t`.
+#[cfg(target_os = "linux")]
+fn signal_process(
+    process: &ProcessInformation,
+    sig: Option<Signal>,
+    queue: Option<u32>,
+) -> Result<(), Errno> {
+    use std::os::fd::AsRawFd;
+    use uucore::libc;
+
+    let signal = sig.map_or(0, Signal::as_raw);
+    let info = queue.map(|value| queue_info(signal, value));
+    let info_ptr = info
+        .as_ref()
+        .map_or(std::ptr::null(), |info| info as *const libc::siginfo_t);
+
+    // Safety: the pidfd is open, and the siginfo outlives the calls
+    let result = match (process.pidfd(), info_ptr.is_null()) {
+        (Some(pidfd), _) => unsafe {
+            libc::syscall(
+                libc::SYS_pidfd_send_signal,
+                pidfd.as_raw_fd(),
+                signal,
+                info_ptr,
+                0,
+            )
+        },
+        (None, false) => unsafe {
+            libc::syscall(
+                libc::SYS_rt_sigqueueinfo,
+                process.pid as libc::pid_t,
+                signal,
+                info_ptr,
+            )
+        },
+        (None, true) => return send_signal(process.pid, sig),
+    };
+    if result == -1 {
+        Err(Errno::from_io_error(&Error::last_os_error()).unwrap_or(Errno::INVAL))
+    } else {
+        Ok(())
+    }
+}
+
+/// The fields of `siginfo_t` that `sigqueue(3)` fills, in their C layout.
+#[cfg(target_os = "linux")]
+#[repr(C)]
+struct QueueInfo {
+    signo: uucore::libc::c_int,
+    #[cfg(not(any(
+        target_arch = "mips",
+        target_arch = "mips32r6",
+        target_arch = "mips64",
+        target_arch = "mips64r6"
+    )))]
+    errno: uucore::libc::c_int,
+    code: uucore::libc::c_int,
+    // MIPS has the code before the errno
+    #[cfg(any(
+        target_arch = "mips",
+        target_arch = "mips32r6",
+        target_arch = "mips64",
+        target_arch = "mips64r6"
+    ))]
+    errno: uucore::libc::c_int,
+    // In a union, aligned like the pointer of the sigval it ends with
+    _align: [usize; 0],
+    pid: uucore::libc::pid_t,
+    uid: uucore::libc::uid_t,
+    // The int member of the sigval union, at its start
+    value: uucore::libc::c_int,
+}
+
+// It is written over the start of a siginfo_t
+#[cfg(target_os = "linux")]
+const _: () =
+    assert!(std::mem::size_of::<QueueInfo>() <= std::mem::size_of::<uucore::libc::siginfo_t>());
+
+#[cfg(target_os = "linux")]
+fn queue_info(signal: i32, value: u32) -> uucore::libc::siginfo_t {
+    use rustix::process::{getpid, getuid};
+    use uucore::libc;
+
+    // Safety: siginfo_t is plain data, for which zeroes are valid
+    let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+    let fields = QueueInfo {
+        signo: signal,
+        errno: 0,
+        code: libc::SI_QUEUE,
+        _align: [],
+        pid: getpid().as_raw_pid(),
+        uid: getuid().as_raw(),
+        value: value as libc::c_int,
+    };
+    // Safety: QueueInfo is smaller than siginfo_t, whose alignment may differ
+    unsafe { (&raw mut info).cast::<QueueInfo>().write_unaligned(fields) };
+    info
+}
+
 #[allow(clippy::cognitive_complexity)]
 pub fn uu_app() -> Command {
     Command::new(uucore::util_name())
         .version(crate_version!())
         .about("Kills processes based on name and other attributes")
-        .override_usage("pkill [options] <pattern>")
+        .override_usage("pkill [options] <pattern>...")
         .args_override_self(true)
         .args([
             // arg!(-<sig>                    "signal to send (either number or name)"),
             arg!(-q --queue <value>        "integer value to be sent with the signal")
                 .value_parser(value_parser!(u32)),
             arg!(-e --echo                 "display what is killed"),
+            arg!(--"kill-after" <duration> "send SIGKILL to the processes still running after duration")
+                .value_parser(|duration: &str| parse_time::from_str(duration, true)),
         ])
         .args(process_matcher::clap_args(
             "Name of the process to kill",
             false,
         ))
 }
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    #[test]
+    fn test_kill_exited() {
+        let mut child = Command::new("sleep").arg("10").spawn().unwrap();
+        let mut process = ProcessInformation::from_pid(child.id() as usize).unwrap();
+        assert!(process.open_pidfd().unwrap());
+        child.kill().unwrap();
+        child.wait().unwrap();
+
+        assert!(kill(&[process], Some(Signal::KILL), None, false).is_empty());
+        assert_eq!(uucore::error::get_exit_code(), 0);
+    }
+}
@@ -6,10 +6,11 @@
 #[cfg(target_os = "linux")]
 pub(crate) fn renice(pid: u32, nice_value: i32) -> uucore::error::UResult<()> {
     use rustix::process::{setpriority_process, Pid};
-    use uucore::error::USimpleError;
+    use uucore::error::{strip_errno, USimpleError};
 
     let pid = Pid::from_raw(pid as i32);
-    setpriority_process(pid, nice_value).map_err(|_| USimpleError::new(0, "Permission Denied"))
+    setpriority_process(pid, nice_value)
+        .map_err(|e| USimpleError::new(0, strip_errno(&std::io::Error::from(e))))
 }
 
 #[cfg(unix)]
@@ -20,5 +21,5 @@ pub(crate) fn kill_process(pid: u32, sig: usize) -> uucore::error::UResult<()> {
     use uucore::error::USimpleError;
 
     signal::kill(Pid::from_raw(pid as i32), Signal::try_from(sig as i32)?)
-        .map_err(|_| USimpleError::new(0, "Permission Denied"))
+        .map_err(|e| USimpleError::new(0, e.desc()))
 }
@@ -0,0 +1,253 @@
+// This file is part of the uutils procps package.
+//
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+use crate::tui::color::ColorScheme;
+use crate::tui::inspect::{default_entries, InspectEntry, InspectSource};
+use crate::tui::stat::TuiStat;
+use ratatui::style::Color;
+use std::fmt::Write;
+use std::io::Error;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+// GNU top's toprc uses a different layout, so ours lives in a file of its own.
+pub fn get_rc_default_path() -> Option<PathBuf> {
+    let mut path = dirs::config_dir()?;
+    path.push("procps");
+    path.push("uutoprc");
+    Some(path)
+}
+
+/// Apply the saved configuration, if any, on top of the defaults.
+pub(crate) fn load(stat: &mut TuiStat) {
+    if let Some(contents) = get_rc_default_path().and_then(|p| std::fs::read_to_string(p).ok()) {
+        read_rc(&contents, stat);
+    }
+}
+
+/// Write the current configuration, what the 'W' command does.
+pub(crate) fn save(stat: &TuiStat) -> Result<PathBuf, Error> {
+    let path = get_rc_default_path().ok_or_else(|| Error::other("no configuration directory"))?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, create_rc(stat))?;
+    Ok(path)
+}
+
+fn read_rc(contents: &str, stat: &mut TuiStat) {
+    let mut in_global = false;
+    let mut in_window = false;
+    let mut in_inspect = false;
+
+    for line in contents.lines() {
+        let line = line.trim_ascii();
+        if line.starts_with('#') || line.is_empty() {
+            continue;
+        }
+
+        if line.starts_with('[') {
+            in_global = line == "[Global]";
+            // there is only the default window so far
+            in_window = line == "[Window 1:Def]";
+            in_inspect = line == "[Inspect]";
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let (key, value) = (key.trim_ascii(), value.trim_ascii());
+
+        if in_global {
+            match key {
+                "Delay" => {
+                    if let Some(delay) = value
+                        .parse::<f32>()
+                        .ok()
+                        .and_then(|v| Duration::try_from_secs_f32(v).ok())
+                    {
+                        stat.delay = delay;
+                    }
+                }
+                "IrixMode" => set_bool(&mut stat.irix_mode, value),
+                "ShowZeros" => set_bool(&mut stat.show_zeros, value),
+                _ => {}
+            }
+        } else if in_window {
+            match key {
+                "Colorful" => set_bool(&mut stat.colorful, value),
+                "ShowLoadAvg" => set_bool(&mut stat.show_load_avg, value),
+                "FullCommandLine" => set_bool(&mut stat.full_command_line, value),
+                "HighlightSorted" => set_bool(&mut stat.highlight_sorted, value),
+                "HighlightBold" => set_bool(&mut stat.highlight_bold, value),
+                "SortByPid" => set_bool(&mut stat.sort_by_pid, value),
+                "CumulativeTime" => set_bool(&mut stat.cumulative_time, value),
+                "SortField" if crate::selected_fields().iter().any(|f| f == value) => {
+                    stat.sorter = value.to_string();
+                }
+                "SummaryColor" => set_color(&mut stat.color_scheme.summary, value),
+                "MessagesColor" => set_color(&mut stat.color_scheme.messages, value),
+                "HeadsColor" => set_color(&mut stat.color_scheme.heads, value),
+                "TasksColor" => set_color(&mut stat.color_scheme.tasks, value),
+                "Bold" => set_bool(&mut stat.color_scheme.bold, value),
+                _ => {}
+            }
+        } else if in_inspect {
+            let source = match key.split_once(' ') {
+                Some(("File", _)) => InspectSource::File(value.into()),
+                Some(("Pipe", _)) => InspectSource::Pipe(value.into()),
+                _ => continue,
+            };
+            let name = key.split_once(' ').unwrap().1.trim_ascii();
+            if !name.is_empty() && stat.inspect_entries.iter().all(|e| e.name != name) {
+                stat.inspect_entries.push(InspectEntry {
+                    name: name.into(),
+                    source,
+                });
+            }
+        }
+    }
+}
+
+fn set_bool(field: &mut bool, value: &str) {
+    if let Ok(value) = value.parse() {
+        *field = value;
+    }
+}
+
+fn set_color(field: &mut Color, value: &str) {
+    if let Ok(value) = Color::from_str(value) {
+        *field = value;
+    }
+}
+
+fn create_rc(stat: &TuiStat) -> String {
+    let ColorScheme {
+        summary,
+        messages,
+        heads,
+        tasks,
+        bold,
+    } = stat.color_scheme;
+
+    let mut contents = "# top's Config File\n".to_string()
+        + "\n"
+        + "# All the entries are case sensitive.\n"
+        + "# Unsupported entries are ignored!\n"
+        + "\n";
+
+    // Writing to a String never fails.
+    let _ = write!(
+        contents,
+        "[Global]\n\
+         Delay={delay}\n\
+         IrixMode={irix_mode}\n\
+         ShowZeros={show_zeros}\n\
+         \n\
+         [Window 1:Def]\n\
+         Colorful={colorful}\n\
+         ShowLoadAvg={show_load_avg}\n\
+         FullCommandLine={full_command_line}\n\
+         HighlightSorted={highlight_sorted}\n\
+         HighlightBold={highlight_bold}\n\
+         SortByPid={sort_by_pid}\n\
+         SortField={sorter}\n\
+         CumulativeTime={cumulative_time}\n\
+         \n\
+         # Colors are names (red, lightcyan, reset...), 0-255 or #rrggbb\n\
+         SummaryColor={summary}\n\
+         MessagesColor={messages}\n\
+         HeadsColor={heads}\n\
+         TasksColor={tasks}\n\
+         Bold={bold}\n",
+        delay = stat.delay.as_secs_f32(),
+        irix_mode = stat.irix_mode,
+        show_zeros = stat.show_zeros,
+        colorful = stat.colorful,
+        show_load_avg = stat.show_load_avg,
+        full_command_line = stat.full_command_line,
+        highlight_sorted = stat.highlight_sorted,
+        highlight_bold = stat.highlight_bold,
+        sort_by_pid = stat.sort_by_pid,
+        sorter = stat.sorter,
+        cumulative_time = stat.cumulative_time,
+        summary = summary.to_string().to_lowercase(),
+        messages = messages.to_string().to_lowercase(),
+        heads = heads.to_string().to_lowercase(),
+        tasks = tasks.to_string().to_lowercase(),
+    );
+
+    contents += "\n\
+                 [Inspect]\n\
+                 \n\
+                 # Extra entries of the inspect screen, %d is replaced by the PID\n\
+                 #File Maps=/proc/%d/maps\n\
+                 #Pipe Open Files=lsof -P -p %d 2>&1\n";
+    let defaults = default_entries();
+    for entry in stat
+        .inspect_entries
+        .iter()
+        .filter(|e| !defaults.contains(e))
+    {
+        let _ = match &entry.source {
+            InspectSource::File(path) => writeln!(contents, "File {}={path}", entry.name),
+            InspectSource::Pipe(command) => writeln!(contents, "Pipe {}={command}", entry.name),
+            InspectSource::FileDescriptors => Ok(()),
+        };
+    }
+
+    contents
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tui::color::ColorTheme;
+
+    #[test]
+    fn test_rc_round_trip() {
+        let mut stat = TuiStat::new();
+        stat.delay = Duration::from_millis(2500);
+        stat.colorful = false;
+        stat.highlight_bold = true;
+        stat.cumulative_time = true;
+        stat.color_scheme = ColorTheme::Light.scheme();
+        stat.color_scheme.tasks = Color::Rgb(0x12, 0x34, 0x56);
+        stat.inspect_entries.push(InspectEntry {
+            name: "Open Files (lsof)".into(),
+            source: InspectSource::Pipe("lsof -P -p %d 2>&1 | grep -v =".into()),
+        });
+
+        let mut loaded = TuiStat::new();
+        read_rc(&create_rc(&stat), &mut loaded);
+
+        assert_eq!(loaded.delay, stat.delay);
+        assert!(!loaded.colorful);
+        assert!(loaded.highlight_bold);
+        assert!(loaded.cumulative_time);
+        assert_eq!(loaded.color_scheme, stat.color_scheme);
+        assert_eq!(loaded.inspect_entries, stat.inspect_entries);
+    }
+
+    #[test]
+    fn test_rc_ignores_unsupported_entries() {
+        let mut stat = TuiStat::new();
+        read_rc(
+            "[Global]\nDelay=-1\nUnknown=1\n\
+             [Window 2:Job]\nSummaryColor=blue\n\
+             [Window 1:Def]\nHeadsColor=not-a-color\nSortField=NOPE\nTasksColor=3\n",
+            &mut stat,
+        );
+
+        let default = TuiStat::new();
+        assert_eq!(stat.delay, default.delay);
+        assert_eq!(stat.sorter, default.sorter);
+        assert_eq!(stat.color_scheme.summary, default.color_scheme.summary);
+        assert_eq!(stat.color_scheme.heads, default.color_scheme.heads);
+        assert_eq!(stat.color_scheme.tasks, Color::Indexed(3));
+    }
+}
@@ -15,7 +15,7 @@ use std::{
     str::FromStr,
     sync::{OnceLock, RwLock},
 };
-use sysinfo::{Pid, System, Users};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System, Users};
 
 static SYSINFO: OnceLock<RwLock<System>> = OnceLock::new();
 
@@ -23,6 +23,19 @@ pub fn sysinfo() -> &'static RwLock<System> {
     SYSINFO.get_or_init(|| RwLock::new(System::new_all()))
 }
 
+/// When the task started, in seconds since the epoch, which tells it apart
+/// from a later one reusing its PID. None once it is gone.
+pub(crate) fn start_time(pid: u32) -> Option<u64> {
+    let pid = Pid::from_u32(pid);
+    let mut system = sysinfo().write().unwrap();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        true,
+        ProcessRefreshKind::nothing(),
+    );
+    system.process(pid).map(|process| process.start_time())
+}
+
 pub trait Column {
     fn as_string(&self, tui_stat: &TuiStat) -> String;
     fn cmp_dyn(&self, other: &dyn Column) -> Ordering;
@@ -401,17 +414,46 @@ fn s(pid: u32, _stat: Stat) -> Box<dyn Column> {
     )
 }
 
-fn time_plus(pid: u32, _stat: Stat) -> Box<dyn Column> {
+fn time_plus(pid: u32, stat: Stat) -> Box<dyn Column> {
     let binding = sysinfo().read().unwrap();
     let Some(proc) = binding.process(Pid::from_u32(pid)) else {
         return TimeValue::new_boxed(0.0);
     };
 
-    let second = proc.accumulated_cpu_time() as f64 / 1000.0;
+    let mut second = proc.accumulated_cpu_time() as f64 / 1000.0;
+    if stat.1.cumulative_time {
+        second += get_children_cpu_time(pid);
+    }
 
     TimeValue::new_boxed(second)
 }
 
+/// CPU time in seconds of the dead children `pid` has waited for,
+/// the `cutime` and `cstime` fields of `/proc/<pid>/stat`.
+#[cfg(target_os = "linux")]
+fn get_children_cpu_time(pid: u32) -> f64 {
+    let Ok(content) = std::fs::read_to_string(format!("/proc/{pid}/stat")) else {
+        return 0.0;
+    };
+    // the command name may contain spaces and parentheses
+    let Some((_, fields)) = content.rsplit_once(')') else {
+        return 0.0;
+    };
+    // fields after the name start at the 3rd one (state)
+    let ticks: u64 = fields
+        .split_whitespace()
+        .skip(13)
+        .take(2)
+        .filter_map(|it| it.parse::<u64>().ok())
+        .sum();
+    ticks as f64 / rustix::param::clock_ticks_per_second() as f64
+}
+
+#[cfg(not(target_os = "linux"))]
+fn get_children_cpu_time(_pid: u32) -> f64 {
+    0.0
+}
+
 fn mem(pid: u32, _stat: Stat) -> Box<dyn Column> {
     let binding = sysinfo().read().unwrap();
     let Some(proc) = binding.process(Pid::from_u32(pid)) else {
@@ -516,3 +558,18 @@ fn command(pid: u32, stat: Stat) -> Box<dyn Column> {
     let full_command_line = stat.1.full_command_line;
     Box::new(get_command(pid, full_command_line))
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_start_time() {
+        assert!(start_time(std::process::id()).is_some());
+
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let pid = child.id();
+        child.wait().unwrap();
+        assert_eq!(start_time(pid), None);
+    }
+}
@@ -0,0 +1,441 @@
+// This file is part of the uutils procps package.
+//
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+//! The `--record` log, read back with `--replay`.
+//!
+//! The file starts with `MAGIC`, followed by one frame per refresh. A frame is
+//! its length and the encoded header and task table. Numbers are little endian,
+//! lengths and counts are `u32` and strings are prefixed with their length.
+
+use crate::header::{Header, Memory, Task, Uptime};
+use crate::ProcList;
+use std::fs::{File, OpenOptions};
+use std::io::{Error, ErrorKind, Read, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use uu_vmstat::CpuLoad;
+
+const MAGIC: &[u8] = b"uutop-record-1\n";
+
+pub(crate) struct Recorder {
+    file: File,
+}
+
+impl Recorder {
+    pub fn open(path: &Path) -> Result<Self, Error> {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        if file.metadata()?.len() == 0 {
+            file.write_all(MAGIC)?;
+        } else {
+            let mut magic = vec![0; MAGIC.len()];
+            File::open(path)?.read_exact(&mut magic)?;
+            if magic != MAGIC {
+                return Err(Error::new(ErrorKind::InvalidData, "not a top record file"));
+            }
+        }
+        Ok(Self { file })
+    }
+
+    pub fn write(&mut self, header: &Header, proc_list: &ProcList) -> Result<(), Error> {
+        let mut frame = Encoder::default();
+        frame.u64(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|it| it.as_secs())
+                .unwrap_or_default(),
+        );
+        frame.header(header);
+        frame.proc_list(proc_list);
+
+        let mut buf = (frame.0.len() as u32).to_le_bytes().to_vec();
+        buf.extend(frame.0);
+        // a single write keeps frames whole when several instances append
+        self.file.write_all(&buf)
+    }
+}
+
+/// Frames of a record file, decoded when they are displayed.
+pub(crate) struct Recording {
+    frames: Vec<Vec<u8>>,
+}
+
+impl Recording {
+    pub fn open(path: &Path) -> Result<Self, Error> {
+        let mut content = Vec::new();
+        File::open(path)?.read_to_end(&mut content)?;
+        Self::parse(&content)
+    }
+
+    fn parse(content: &[u8]) -> Result<Self, Error> {
+        let Some(mut content) = content.strip_prefix(MAGIC) else {
+            return Err(Error::new(ErrorKind::InvalidData, "not a top record file"));
+        };
+
+        let mut frames = Vec::new();
+        while content.len() >= 4 {
+            let len = u32::from_le_bytes(content[..4].try_into().unwrap()) as usize;
+            content = &content[4..];
+            if content.len() < len {
+                // the last frame was cut short, e.g. by a full disk
+                break;
+            }
+            frames.push(content[..len].to_vec());
+            content = &content[len..];
+        }
+
+        if frames.is_empty() {
+            return Err(Error::new(ErrorKind::InvalidData, "no frames recorded"));
+        }
+        Ok(Self { frames })
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Time of the frame in seconds since the epoch
+    pub fn timestamp(&self, nth: usize) -> u64 {
+        Decoder(&self.frames[nth]).u64().unwrap_or_default()
+    }
+
+    pub fn frame(&self, nth: usize) -> Result<(Header, ProcList), Error> {
+        let mut decoder = Decoder(&self.frames[nth]);
+        decoder.u64()?;
+        let header = decoder.header()?;
+        let proc_list = decoder.proc_list()?;
+        Ok((header, proc_list))
+    }
+}
+
+/// Position in the recording played back with `--replay`.
+pub(crate) struct Replay {
+    pub recording: Recording,
+    pub position: usize,
+    pub paused: bool,
+}
+
+impl Replay {
+    pub fn new(recording: Recording) -> Self {
+        Self {
+            recording,
+            position: 0,
+            paused: false,
+        }
+    }
+
+    /// Move by `delta` frames, returns whether the position changed.
+    pub fn seek(&mut self, delta: isize) -> bool {
+        let last = self.recording.len() - 1;
+        let position = self.position.saturating_add_signed(delta).min(last);
+        let changed = position != self.position;
+        self.position = position;
+        changed
+    }
+
+    pub fn at_end(&self) -> bool {
+        self.position + 1 >= self.recording.len()
+    }
+
+    pub fn status(&self) -> String {
+        let time = jiff::Timestamp::from_second(self.recording.timestamp(self.position) as i64)
+            .map(|ts| {
+                ts.to_zoned(jiff::tz::TimeZone::system())
+                    .strftime("%Y-%m-%d %H:%M:%S")
+                    .to_string()
+            })
+            .unwrap_or_default();
+        format!(
+            "  replay: frame {}/{} recorded {time}{}",
+            self.position + 1,
+            self.recording.len(),
+            if self.paused { " (paused)" } else { "" }
+        )
+    }
+}
+
+#[derive(Default)]
+struct Encoder(Vec<u8>);
+
+impl Encoder {
+    fn u32(&mut self, value: u32) {
+        self.0.extend(value.to_le_bytes());
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.0.extend(value.to_le_bytes());
+    }
+
+    fn f64(&mut self, value: f64) {
+        self.0.extend(value.to_le_bytes());
+    }
+
+    fn str(&mut self, value: &str) {
+        self.u32(value.len() as u32);
+        self.0.extend(value.as_bytes());
+    }
+
+    fn header(&mut self, header: &Header) {
+        let uptime = &header.uptime;
+        for s in [
+            &uptime.time,
+            &uptime.uptime,
+            &uptime.user,
+            &uptime.load_average,
+        ] {
+            self.str(s);
+        }
+
+        let task = &header.task;
+        for n in [
+            task.total,
+            task.running,
+            task.sleeping,
+            task.stopped,
+            task.zombie,
+        ] {
+            self.u64(n as u64);
+        }
+
+        self.u32(header.cpu.len() as u32);
+        for (tag, load) in &header.cpu {
+            self.str(tag);
+            for n in [
+                load.user,
+                load.nice,
+                load.system,
+                load.idle,
+                load.io_wait,
+                load.hardware_interrupt,
+                load.software_interrupt,
+                load.steal_time,
+                load.guest,
+                load.guest_nice,
+            ] {
+                self.f64(n);
+            }
+        }
+
+        let mem = &header.memory;
+        for n in [
+            mem.total,
+            mem.free,
+            mem.used,
+            mem.buff_cache,
+            mem.available,
+            mem.total_swap,
+            mem.free_swap,
+            mem.used_swap,
+        ] {
+            self.u64(n);
+        }
+    }
+
+    fn proc_list(&mut self, proc_list: &ProcList) {
+        self.u32(proc_list.fields.len() as u32);
+        for field in &proc_list.fields {
+            self.str(field);
+        }
+        self.u32(proc_list.collected.len() as u32);
+        for (pid, columns) in &proc_list.collected {
+            self.u32(*pid);
+            // one column per field
+            for column in columns {
+                self.str(column);
+            }
+        }
+    }
+}
+
+struct Decoder<'a>(&'a [u8]);
+
+impl Decoder<'_> {
+    fn bytes(&mut self, len: usize) -> Result<&[u8], Error> {
+        if self.0.len() < len {
+            return Err(Error::new(ErrorKind::UnexpectedEof, "truncated frame"));
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, Error> {
+        Ok(u64::from_le_bytes(self.bytes(8)?.try_into().unwrap()))
+    }
+
+    fn len(&mut self) -> Result<usize, Error> {
+        Ok(self.u32()? as usize)
+    }
+
+    fn f64(&mut self) -> Result<f64, Error> {
+        Ok(f64::from_le_bytes(self.bytes(8)?.try_into().unwrap()))
+    }
+
+    fn str(&mut self) -> Result<String, Error> {
+        let len = self.len()?;
+        Ok(String::from_utf8_lossy(self.bytes(len)?).into_owned())
+    }
+
+    fn header(&mut self) -> Result<Header, Error> {
+        let uptime = Uptime {
+            time: self.str()?,
+            uptime: self.str()?,
+            user: self.str()?,
+            load_average: self.str()?,
+        };
+        let task = Task {
+            total: self.u64()? as usize,
+            running: self.u64()? as usize,
+            sleeping: self.u64()? as usize,
+            stopped: self.u64()? as usize,
+            zombie: self.u64()? as usize,
+        };
+        let cpu = (0..self.len()?)
+            .map(|_| {
+                Ok((
+                    self.str()?,
+                    CpuLoad {
+                        user: self.f64()?,
+                        nice: self.f64()?,
+                        system: self.f64()?,
+                        idle: self.f64()?,
+                        io_wait: self.f64()?,
+                        hardware_interrupt: self.f64()?,
+                        software_interrupt: self.f64()?,
+                        steal_time: self.f64()?,
+                        guest: self.f64()?,
+                        guest_nice: self.f64()?,
+                    },
+                ))
+            })
+            .collect::<Result<_, Error>>()?;
+        let memory = Memory {
+            total: self.u64()?,
+            free: self.u64()?,
+            used: self.u64()?,
+            buff_cache: self.u64()?,
+            available: self.u64()?,
+            total_swap: self.u64()?,
+            free_swap: self.u64()?,
+            used_swap: self.u64()?,
+        };
+        Ok(Header {
+            uptime,
+            task,
+            cpu,
+            memory,
+        })
+    }
+
+    fn proc_list(&mut self) -> Result<ProcList, Error> {
+        let fields = (0..self.len()?)
+            .map(|_| self.str())
+            .collect::<Result<Vec<_>, Error>>()?;
+        let collected = (0..self.len()?)
+            .map(|_| {
+                let pid = self.u32()?;
+                let columns = (0..fields.len())
+                    .map(|_| self.str())
+                    .collect::<Result<_, Error>>()?;
+                Ok((pid, columns))
+            })
+            .collect::<Result<_, Error>>()?;
+        Ok(ProcList { fields, collected })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sample() -> (Header, ProcList) {
+        let header = Header {
+            uptime: Uptime {
+                time: "10:00:00".into(),
+                uptime: "up 1 day".into(),
+                user: "1 user".into(),
+                load_average: "load average: 0.1, 0.2, 0.3".into(),
+            },
+            task: Task {
+                total: 3,
+                running: 1,
+                sleeping: 2,
+                stopped: 0,
+                zombie: 0,
+            },
+            cpu: vec![(
+                "Cpu0".into(),
+                CpuLoad {
+                    user: 1.5,
+                    nice: 0.0,
+                    system: 2.5,
+                    idle: 96.0,
+                    io_wait: 0.0,
+                    hardware_interrupt: 0.0,
+                    software_interrupt: 0.0,
+                    steal_time: 0.0,
+                    guest: 0.0,
+                    guest_nice: 0.0,
+                },
+            )],
+            memory: Memory {
+                total: 8,
+                free: 4,
+                used: 2,
+                buff_cache: 2,
+                available: 5,
+                total_swap: 0,
+                free_swap: 0,
+                used_swap: 0,
+            },
+        };
+        let proc_list = ProcList {
+            fields: vec!["PID".into(), "COMMAND".into()],
+            collected: vec![(1, vec!["1".into(), "init".into()])],
+        };
+        (header, proc_list)
+    }
+
+    #[test]
+    fn test_frame_round_trip() {
+        let (header, proc_list) = sample();
+        let mut frame = Encoder::default();
+        frame.u64(42);
+        frame.header(&header);
+        frame.proc_list(&proc_list);
+
+        let mut content = MAGIC.to_vec();
+        content.extend((frame.0.len() as u32).to_le_bytes());
+        content.extend(&frame.0);
+        // cut short while being written
+        content.extend(100u32.to_le_bytes());
+        content.extend(&frame.0[..10]);
+
+        let recording = Recording::parse(&content).unwrap();
+        assert_eq!(recording.len(), 1);
+        assert_eq!(recording.timestamp(0), 42);
+
+        let (decoded_header, decoded_list) = recording.frame(0).unwrap();
+        assert_eq!(
+            decoded_header.uptime.load_average,
+            header.uptime.load_average
+        );
+        assert_eq!(decoded_header.task.sleeping, 2);
+        assert_eq!(decoded_header.cpu[0].0, "Cpu0");
+        assert_eq!(decoded_header.cpu[0].1.system, 2.5);
+        assert_eq!(decoded_header.memory.available, 5);
+        assert_eq!(decoded_list.fields, proc_list.fields);
+        assert_eq!(decoded_list.collected, proc_list.collected);
+    }
+
+    #[test]
+    fn test_invalid_recording() {
+        assert!(Recording::parse(b"not a recording").is_err());
+        assert!(Recording::parse(MAGIC).is_err());
+    }
+}
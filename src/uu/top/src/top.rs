@@ -5,23 +5,29 @@
 
 use crate::header::Header;
 use crate::picker::Column;
+use crate::record::{Recorder, Recording, Replay};
+use crate::tui::inspect::Inspect;
 use crate::tui::stat::TuiStat;
-use crate::tui::{handle_input, Tui};
+use crate::tui::{handle_input, handle_replay_input, load_replay_frame, replay_allows, Tui};
 use clap::{arg, crate_version, value_parser, ArgAction, ArgGroup, ArgMatches, Command};
 use picker::{pickers, sysinfo};
 use ratatui::crossterm::event;
 use ratatui::prelude::Widget;
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, RwLock};
 use std::{thread, thread::sleep, time::Duration};
 use sysinfo::{Pid, Users};
+use uucore::display::Quotable;
 use uucore::error::{UResult, USimpleError};
 
 mod action;
+mod config;
 mod field;
 mod header;
 mod picker;
 mod platform;
+mod record;
 mod tui;
 
 #[allow(unused)]
@@ -69,14 +75,33 @@ pub(crate) struct InfoBar {
 pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     let matches = uu_app().try_get_matches_from(args)?;
 
-    // Must refresh twice.
-    // https://docs.rs/sysinfo/0.31.2/sysinfo/struct.System.html#method.refresh_cpu_usage
-    picker::sysinfo().write().unwrap().refresh_all();
-    sleep(Duration::from_millis(200));
-    picker::sysinfo().write().unwrap().refresh_all();
+    let replay = match matches.get_one::<PathBuf>("replay") {
+        Some(path) => Some(Replay::new(Recording::open(path).map_err(|e| {
+            USimpleError::new(1, format!("cannot replay {}: {e}", path.quote()))
+        })?)),
+        None => None,
+    };
+    let recorder = match matches.get_one::<PathBuf>("record") {
+        Some(path) => Some(Recorder::open(path).map_err(|e| {
+            USimpleError::new(1, format!("cannot record to {}: {e}", path.quote()))
+        })?),
+        None => None,
+    };
+
+    if replay.is_none() {
+        // Must refresh twice.
+        // https://docs.rs/sysinfo/0.31.2/sysinfo/struct.System.html#method.refresh_cpu_usage
+        picker::sysinfo().write().unwrap().refresh_all();
+        sleep(Duration::from_millis(200));
+        picker::sysinfo().write().unwrap().refresh_all();
+    }
 
     let settings = Settings::new(&matches);
     let mut tui_stat = TuiStat::new();
+    config::load(&mut tui_stat);
+    if matches.get_flag("accum-time-toggle") {
+        tui_stat.cumulative_time = !tui_stat.cumulative_time;
+    }
 
     let filter = matches
         .get_many::<u32>("pid")
@@ -101,27 +126,76 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     tui_stat.filter = filter;
 
     let settings = Arc::new(settings);
+    let (header, proc_list) = match &replay {
+        Some(replay) => replay
+            .recording
+            .frame(0)
+            .map_err(|e| USimpleError::new(1, format!("cannot replay the first frame: {e}")))?,
+        None => (Header::new(&tui_stat), ProcList::new(&settings, &tui_stat)),
+    };
+    let replaying = replay.is_some();
+    tui_stat.replay = replay;
+
     let tui_stat = Arc::new(RwLock::new(tui_stat));
     let should_update = Arc::new(AtomicBool::new(true));
-    let data = Arc::new(RwLock::new((
-        Header::new(&tui_stat.read().unwrap()),
-        ProcList::new(&settings, &tui_stat.read().unwrap()),
-        None,
-    )));
+    let data = Arc::new(RwLock::new((header, proc_list, None)));
 
     // update
-    {
+    if replaying {
+        let should_update = should_update.clone();
+        let tui_stat = tui_stat.clone();
+        let data = data.clone();
+        thread::spawn(move || loop {
+            let delay = { tui_stat.read().unwrap().delay };
+            sleep(delay);
+            {
+                let mut tui_stat = tui_stat.write().unwrap();
+                let replay = tui_stat.replay.as_mut().unwrap();
+                if replay.paused {
+                    continue;
+                }
+                replay.seek(1);
+                // stay on the last frame instead of quitting
+                replay.paused = replay.at_end();
+                tui_stat.input_message = None;
+            }
+            load_replay_frame(&tui_stat, &data);
+            should_update.store(true, Ordering::Relaxed);
+        });
+    } else {
         let should_update = should_update.clone();
         let tui_stat = tui_stat.clone();
         let data = data.clone();
         let settings = settings.clone();
+        let mut recorder = recorder;
+        if let Some(rec) = recorder.as_mut() {
+            let data = data.read().unwrap();
+            if let Err(e) = rec.write(&data.0, &data.1) {
+                tui_stat.write().unwrap().input_message = Some(format!(" Failed to record: {e} "));
+                recorder = None;
+            }
+        }
         thread::spawn(move || loop {
             let delay = { tui_stat.read().unwrap().delay };
             sleep(delay);
             {
                 let header = Header::new(&tui_stat.read().unwrap());
                 let proc_list = ProcList::new(&settings, &tui_stat.read().unwrap());
-                tui_stat.write().unwrap().input_message = None;
+                {
+                    let mut tui_stat = tui_stat.write().unwrap();
+                    tui_stat.input_message = None;
+                    // forget tasks that are gone, their PID may be reused, but
+                    // keep those only hidden by a filter
+                    tui_stat
+                        .tagged
+                        .retain(|&pid, &mut started| picker::start_time(pid) == Some(started));
+                    if let Some(rec) = recorder.as_mut() {
+                        if let Err(e) = rec.write(&header, &proc_list) {
+                            tui_stat.input_message = Some(format!(" Failed to record: {e} "));
+                            recorder = None;
+                        }
+                    }
+                }
                 let mut data = data.write().unwrap();
                 data.0 = header;
                 data.1 = proc_list;
@@ -142,11 +216,28 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     loop {
         if let Ok(true) = event::poll(Duration::from_millis(20)) {
             if let Ok(e) = event::read() {
+                if replaying {
+                    if handle_replay_input(&e, &tui_stat, &data, &should_update) {
+                        continue;
+                    }
+                    if !replay_allows(&e, &tui_stat) {
+                        continue;
+                    }
+                }
                 if handle_input(e, &settings, &tui_stat, &data, &should_update) {
                     break;
                 }
             }
         }
+        if tui_stat
+            .write()
+            .unwrap()
+            .inspect
+            .as_mut()
+            .is_some_and(Inspect::poll)
+        {
+            should_update.store(true, Ordering::Relaxed);
+        }
 
         if should_update.load(Ordering::Relaxed) {
             terminal.draw(|frame| {
@@ -321,12 +412,17 @@ pub fn uu_app() -> Command {
                 .action(ArgAction::Append)
                 .value_parser(value_parser!(u32))
                 .value_delimiter(','),
-            // arg!(-S  --"accum-time-toggle"                  "reverse last remembered 'S' state"),
+            arg!(-S  --"accum-time-toggle"                  "reverse last remembered 'S' state"),
             // arg!(-s  --"secure-mode"                        "run with secure mode restrictions"),
             arg!(-U  --"filter-any-user"    <USER>          "show only processes owned by USER"),
             arg!(-u  --"filter-only-euser"  <EUSER>         "show only processes owned by USER"),
             // arg!(-w  --width                <COLUMNS>       "change print width [,use COLUMNS]"),
             // arg!(-1  --single-cpu-toggle         "reverse last remembered '1' state"),
+            arg!(--record                   <FILE>          "append every refresh to FILE")
+                .value_parser(value_parser!(PathBuf)),
+            arg!(--replay                   <FILE>          "play back what --record saved in FILE")
+                .value_parser(value_parser!(PathBuf))
+                .conflicts_with_all(["record", "filter"]),
         ])
         .group(ArgGroup::new("filter").args(["pid", "filter-any-user", "filter-only-euser"]))
 }
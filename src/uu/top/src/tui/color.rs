@@ -3,55 +3,291 @@
 // For the full copyright and license information, please view the LICENSE
 // file that was distributed with this source code.
 
-use ratatui::style::{Color, Style};
+use ratatui::style::{Color, Modifier, Style};
+
+/// Colors of the 0-7 numbers accepted on the 'Z' screen.
+pub(crate) const BASIC_COLORS: [(Color, &str); 8] = [
+    (Color::Black, "black"),
+    (Color::Red, "red"),
+    (Color::Green, "green"),
+    (Color::Yellow, "yellow"),
+    (Color::Blue, "blue"),
+    (Color::Magenta, "magenta"),
+    (Color::Cyan, "cyan"),
+    (Color::Gray, "white"),
+];
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) enum ColorTarget {
+    Summary,
+    Messages,
+    Heads,
+    #[default]
+    Tasks,
+}
+
+impl ColorTarget {
+    pub fn from_char(c: char) -> Option<ColorTarget> {
+        match c {
+            'S' => Some(ColorTarget::Summary),
+            'M' => Some(ColorTarget::Messages),
+            'H' => Some(ColorTarget::Heads),
+            'T' => Some(ColorTarget::Tasks),
+            _ => None,
+        }
+    }
+
+    pub fn as_char(&self) -> char {
+        match self {
+            ColorTarget::Summary => 'S',
+            ColorTarget::Messages => 'M',
+            ColorTarget::Heads => 'H',
+            ColorTarget::Tasks => 'T',
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) enum ColorTheme {
+    #[default]
+    Default,
+    Light,
+    HighContrast,
+    Monochrome,
+}
+
+impl ColorTheme {
+    const ALL: [ColorTheme; 4] = [
+        ColorTheme::Default,
+        ColorTheme::Light,
+        ColorTheme::HighContrast,
+        ColorTheme::Monochrome,
+    ];
+
+    pub fn next(&self) -> ColorTheme {
+        match self {
+            ColorTheme::Default => ColorTheme::Light,
+            ColorTheme::Light => ColorTheme::HighContrast,
+            ColorTheme::HighContrast => ColorTheme::Monochrome,
+            ColorTheme::Monochrome => ColorTheme::Default,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ColorTheme::Default => "default",
+            ColorTheme::Light => "light",
+            ColorTheme::HighContrast => "high-contrast",
+            ColorTheme::Monochrome => "monochrome",
+        }
+    }
+
+    pub fn scheme(&self) -> ColorScheme {
+        match self {
+            ColorTheme::Default => ColorScheme {
+                summary: Color::Red,
+                messages: Color::Red,
+                heads: Color::Yellow,
+                tasks: Color::Reset,
+                bold: false,
+            },
+            // yellow and white are barely readable on a light background
+            ColorTheme::Light => ColorScheme {
+                summary: Color::Blue,
+                messages: Color::Magenta,
+                heads: Color::Cyan,
+                tasks: Color::Black,
+                bold: false,
+            },
+            ColorTheme::HighContrast => ColorScheme {
+                summary: Color::White,
+                messages: Color::LightRed,
+                heads: Color::LightCyan,
+                tasks: Color::White,
+                bold: true,
+            },
+            ColorTheme::Monochrome => ColorScheme {
+                summary: Color::Reset,
+                messages: Color::Reset,
+                heads: Color::Reset,
+                tasks: Color::Reset,
+                bold: false,
+            },
+        }
+    }
+}
+
+/// Colors assigned to the four areas of a window, as on the 'Z' screen.
+///
+/// `Color::Reset` keeps the terminal's own color, backgrounds fall back to
+/// reverse video in that case.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ColorScheme {
+    pub summary: Color,
+    pub messages: Color,
+    pub heads: Color,
+    pub tasks: Color,
+    pub bold: bool,
+}
+
+impl Default for ColorScheme {
+    fn default() -> Self {
+        ColorTheme::default().scheme()
+    }
+}
+
+impl ColorScheme {
+    pub fn get(&self, target: ColorTarget) -> Color {
+        match target {
+            ColorTarget::Summary => self.summary,
+            ColorTarget::Messages => self.messages,
+            ColorTarget::Heads => self.heads,
+            ColorTarget::Tasks => self.tasks,
+        }
+    }
+
+    pub fn set(&mut self, target: ColorTarget, color: Color) {
+        match target {
+            ColorTarget::Summary => self.summary = color,
+            ColorTarget::Messages => self.messages = color,
+            ColorTarget::Heads => self.heads = color,
+            ColorTarget::Tasks => self.tasks = color,
+        }
+    }
+}
+
+/// State of the 'Z' screen, committed to `TuiStat` on Enter.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ColorMapping {
+    pub target: ColorTarget,
+    pub scheme: ColorScheme,
+    pub theme: Option<ColorTheme>, // None once the scheme was edited by hand
+    pub colorful: bool,
+}
+
+impl ColorMapping {
+    pub fn new(scheme: ColorScheme, colorful: bool) -> Self {
+        Self {
+            target: ColorTarget::default(),
+            scheme,
+            theme: ColorTheme::ALL.into_iter().find(|t| t.scheme() == scheme),
+            colorful,
+        }
+    }
+
+    pub fn set_color(&mut self, color: Color) {
+        self.scheme.set(self.target, color);
+        self.theme = None;
+    }
+
+    /// Raise or lower the 0-7 color value of the current target.
+    pub fn step_color(&mut self, up: bool) {
+        let n = match color_number(self.scheme.get(self.target)) {
+            Some(n) if up => (n + 1) % BASIC_COLORS.len(),
+            Some(n) => (n + BASIC_COLORS.len() - 1) % BASIC_COLORS.len(),
+            None => 0,
+        };
+        self.set_color(BASIC_COLORS[n].0);
+    }
+
+    pub fn next_theme(&mut self) {
+        let theme = self.theme.map(|t| t.next()).unwrap_or_default();
+        self.scheme = theme.scheme();
+        self.theme = Some(theme);
+    }
+}
+
+/// Number of `color` on the 'Z' screen, if it is one of the basic colors.
+pub(crate) fn color_number(color: Color) -> Option<usize> {
+    BASIC_COLORS.iter().position(|(c, _)| *c == color)
+}
+
+pub(crate) fn color_name(color: Color) -> String {
+    match color_number(color) {
+        Some(n) => BASIC_COLORS[n].1.to_string(),
+        None if color == Color::Reset => "default".to_string(),
+        None => color.to_string().to_lowercase(),
+    }
+}
 
 /// This is the trait used to adjust the TUI color options
+///
+/// `None` means colors are turned off with 'z'.
 pub(crate) trait TuiColorHelper: Sized {
-    fn primary(self, colorful: bool) -> Style;
-    fn bg_primary(self, colorful: bool) -> Style;
-    fn secondary(self, colorful: bool) -> Style;
-    fn bg_secondary(self, colorful: bool) -> Style;
-    fn error(self, colorful: bool) -> Style;
+    fn primary(self, colors: Option<ColorScheme>) -> Style;
+    fn bg_primary(self, colors: Option<ColorScheme>) -> Style;
+    fn secondary(self, colors: Option<ColorScheme>) -> Style;
+    fn bg_secondary(self, colors: Option<ColorScheme>) -> Style;
+    fn error(self, colors: Option<ColorScheme>) -> Style;
+    fn task(self, colors: Option<ColorScheme>) -> Style;
+}
+
+fn with_fg(style: Style, color: Color, bold: bool) -> Style {
+    let style = style.fg(color);
+    if bold {
+        style.add_modifier(Modifier::BOLD)
+    } else {
+        style
+    }
+}
+
+fn with_bg(style: Style, color: Color, bold: bool) -> Style {
+    let style = if color == Color::Reset {
+        style.add_modifier(Modifier::REVERSED)
+    } else {
+        style.fg(Color::Black).bg(color)
+    };
+    if bold {
+        style.add_modifier(Modifier::BOLD)
+    } else {
+        style
+    }
 }
 
 impl TuiColorHelper for Style {
-    fn primary(self, colorful: bool) -> Style {
-        if colorful {
-            self.red()
+    fn primary(self, colors: Option<ColorScheme>) -> Style {
+        if let Some(colors) = colors {
+            with_fg(self, colors.summary, colors.bold)
         } else {
             self
         }
     }
 
-    fn bg_primary(self, colorful: bool) -> Style {
-        let style = self.fg(Color::Black);
-        if colorful {
-            style.bg(Color::Red)
+    fn bg_primary(self, colors: Option<ColorScheme>) -> Style {
+        if let Some(colors) = colors {
+            with_bg(self, colors.summary, colors.bold)
         } else {
-            style.bg(Color::White)
+            self.fg(Color::Black).bg(Color::White)
         }
     }
 
-    fn secondary(self, colorful: bool) -> Style {
-        if colorful {
-            self.yellow()
+    fn secondary(self, colors: Option<ColorScheme>) -> Style {
+        if let Some(colors) = colors {
+            with_fg(self, colors.heads, colors.bold)
         } else {
             self
         }
     }
 
-    fn bg_secondary(self, colorful: bool) -> Style {
-        let style = self.fg(Color::Black);
-        if colorful {
-            style.bg(Color::Yellow)
+    fn bg_secondary(self, colors: Option<ColorScheme>) -> Style {
+        if let Some(colors) = colors {
+            with_bg(self, colors.heads, colors.bold)
         } else {
-            style.bg(Color::White)
+            self.fg(Color::Black).bg(Color::White)
+        }
+    }
+
+    fn error(self, colors: Option<ColorScheme>) -> Style {
+        if let Some(colors) = colors {
+            with_bg(self, colors.messages, colors.bold)
+        } else {
+            self
         }
     }
 
-    fn error(self, colorful: bool) -> Style {
-        if colorful {
-            self.fg(Color::Black).bg(Color::Red)
+    fn task(self, colors: Option<ColorScheme>) -> Style {
+        if let Some(colors) = colors {
+            with_fg(self, colors.tasks, colors.bold)
         } else {
             self
         }
@@ -4,19 +4,25 @@
 // file that was distributed with this source code.
 
 use crate::header::Header;
-use crate::picker::get_command;
+use crate::picker::{get_command, start_time, sysinfo};
 use crate::platform::get_numa_nodes;
+use crate::tui::color::{ColorMapping, ColorTarget, BASIC_COLORS};
+use crate::tui::inspect::Inspect;
 use crate::tui::stat::{CpuValueMode, TuiStat};
 use crate::Filter::{EUser, User};
 use crate::{selected_fields, try_into_uid, InfoBar, ProcList, Settings};
 use ratatui::crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use std::cmp::min;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::RwLock;
+use sysinfo::Pid;
 
 #[derive(Eq, PartialEq, Copy, Clone)]
 pub(crate) enum InputMode {
     Command,
     Input(InputEvent),
+    ColorMapping,
+    Inspect,
 }
 #[derive(Eq, PartialEq, Copy, Clone)]
 pub(crate) enum InputEvent {
@@ -26,6 +32,7 @@ pub(crate) enum InputEvent {
     FilterEUser,
     WidthIncrement,
     Delay,
+    InspectProc,
     #[cfg(target_os = "linux")]
     ReniceProc,
     #[cfg(target_os = "linux")]
@@ -34,6 +41,17 @@ pub(crate) enum InputEvent {
     KillProc,
     #[cfg(unix)]
     KillSignal,
+    #[cfg(unix)]
+    ConfirmTagged,
+}
+
+/// Action waiting for confirmation before it is applied to the tagged tasks.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum TaggedAction {
+    #[cfg(target_os = "linux")]
+    Renice(i32),
+    #[cfg(unix)]
+    Signal(usize),
 }
 
 macro_rules! char {
@@ -45,6 +63,7 @@ macro_rules! char {
     };
 }
 
+#[allow(clippy::cognitive_complexity)]
 pub fn handle_input(
     e: Event,
     settings: &Settings,
@@ -186,9 +205,38 @@ pub fn handle_input(
                 }
                 let pid = data.1.collected[nth].0;
                 tui_stat.input_value.clear();
-                tui_stat.input_label = format!("PID to signal/kill [default pid = {}]", pid);
-                tui_stat.selected_process = Some(pid);
-                tui_stat.input_mode = InputMode::Input(InputEvent::KillProc);
+                if tui_stat.tagged.is_empty() {
+                    tui_stat.input_label = format!("PID to signal/kill [default pid = {}]", pid);
+                    tui_stat.selected_process = Some(pid);
+                    tui_stat.input_mode = InputMode::Input(InputEvent::KillProc);
+                } else {
+                    tui_stat.input_label = format!(
+                        "Send {} tagged tasks signal [15/sigterm]",
+                        tui_stat.tagged.len()
+                    );
+                    tui_stat.input_mode = InputMode::Input(InputEvent::KillSignal);
+                }
+
+                should_update.store(true, Ordering::Relaxed);
+            }
+            #[cfg(unix)]
+            char!(' ') => {
+                let data = data.read().unwrap();
+                let mut tui_stat = tui_stat.write().unwrap();
+                let mut nth = tui_stat.list_offset;
+                if data.1.collected.is_empty() {
+                    return false;
+                }
+                if data.1.collected.len() <= nth {
+                    nth = data.1.collected.len() - 1;
+                }
+                let pid = data.1.collected[nth].0;
+                if tui_stat.tagged.remove(&pid).is_none() {
+                    if let Some(started) = start_time(pid) {
+                        tui_stat.tagged.insert(pid, started);
+                    }
+                }
+                tui_stat.input_message = Some(format!(" {} tasks tagged ", tui_stat.tagged.len()));
 
                 should_update.store(true, Ordering::Relaxed);
             }
@@ -235,10 +283,29 @@ pub fn handle_input(
                 }
                 let pid = data.1.collected[nth].0;
                 tui_stat.input_value.clear();
-                tui_stat.input_label = format!("PID to renice [default pid = {}]", pid);
-                tui_stat.selected_process = Some(pid);
-                tui_stat.input_mode = InputMode::Input(InputEvent::ReniceProc);
+                if tui_stat.tagged.is_empty() {
+                    tui_stat.input_label = format!("PID to renice [default pid = {}]", pid);
+                    tui_stat.selected_process = Some(pid);
+                    tui_stat.input_mode = InputMode::Input(InputEvent::ReniceProc);
+                } else {
+                    tui_stat.input_label =
+                        format!("Renice {} tagged tasks to value", tui_stat.tagged.len());
+                    tui_stat.input_mode = InputMode::Input(InputEvent::ReniceValue);
+                }
+
+                should_update.store(true, Ordering::Relaxed);
+            }
+            char!('S') => {
+                {
+                    let mut stat = tui_stat.write().unwrap();
+                    stat.cumulative_time = !stat.cumulative_time;
+                    stat.input_message = Some(format!(
+                        " Cumulative time {} ",
+                        if stat.cumulative_time { "On" } else { "Off" }
+                    ));
+                }
 
+                data.write().unwrap().1 = ProcList::new(settings, &tui_stat.read().unwrap());
                 should_update.store(true, Ordering::Relaxed);
             }
             char!('t') => {
@@ -291,6 +358,14 @@ pub fn handle_input(
 
                 should_update.store(true, Ordering::Relaxed);
             }
+            char!('W') => {
+                let mut stat = tui_stat.write().unwrap();
+                stat.input_message = Some(match crate::config::save(&stat) {
+                    Ok(path) => format!(" Wrote configuration to '{}' ", path.display()),
+                    Err(e) => format!(" Failed to write configuration: {e} "),
+                });
+                should_update.store(true, Ordering::Relaxed);
+            }
             char!('X') => {
                 let mut stat = tui_stat.write().unwrap();
                 stat.input_label = "width incr is 0, change to (0 default, -1 auto) ".into();
@@ -309,6 +384,30 @@ pub fn handle_input(
                 stat.colorful = !stat.colorful;
                 should_update.store(true, Ordering::Relaxed);
             }
+            char!('Y') => {
+                let data = data.read().unwrap();
+                let mut tui_stat = tui_stat.write().unwrap();
+                let mut nth = tui_stat.list_offset;
+                if data.1.collected.is_empty() {
+                    return false;
+                }
+                if data.1.collected.len() <= nth {
+                    nth = data.1.collected.len() - 1;
+                }
+                let pid = data.1.collected[nth].0;
+                tui_stat.input_value.clear();
+                tui_stat.input_label = format!("To inspect, enter PID [default pid = {}]", pid);
+                tui_stat.selected_process = Some(pid);
+                tui_stat.input_mode = InputMode::Input(InputEvent::InspectProc);
+
+                should_update.store(true, Ordering::Relaxed);
+            }
+            char!('Z') => {
+                let mut stat = tui_stat.write().unwrap();
+                stat.color_mapping = Some(ColorMapping::new(stat.color_scheme, stat.colorful));
+                stat.input_mode = InputMode::ColorMapping;
+                should_update.store(true, Ordering::Relaxed);
+            }
             char!('0') => {
                 {
                     // drop the lock as soon as possible
@@ -444,6 +543,11 @@ pub fn handle_input(
                     KeyCode::Esc => {
                         let mut stat = tui_stat.write().unwrap();
                         stat.reset_input();
+                        #[cfg(unix)]
+                        if input_event == InputEvent::ConfirmTagged {
+                            // the info bar lists the tasks to confirm
+                            data.write().unwrap().2 = None;
+                        }
                         should_update.store(true, Ordering::Relaxed);
                     }
                     KeyCode::Backspace => {
@@ -460,10 +564,93 @@ pub fn handle_input(
                 }
             }
         }
+        InputMode::ColorMapping => {
+            if let Event::Key(key) = e {
+                handle_color_mapping(key.code, tui_stat);
+                should_update.store(true, Ordering::Relaxed);
+            }
+        }
+        InputMode::Inspect => match e {
+            Event::Key(key) => {
+                handle_inspect(key.code, tui_stat);
+                should_update.store(true, Ordering::Relaxed);
+            }
+            Event::Resize(_, _) => should_update.store(true, Ordering::Relaxed),
+            _ => {}
+        },
     }
     false
 }
 
+fn handle_inspect(code: KeyCode, tui_stat: &RwLock<TuiStat>) {
+    let mut stat = tui_stat.write().unwrap();
+    let stat = &mut *stat;
+    let Some(inspect) = stat.inspect.as_mut() else {
+        stat.reset_input();
+        return;
+    };
+    let entries = &stat.inspect_entries;
+    match code {
+        KeyCode::Left => {
+            let selected = inspect.selected.checked_sub(1).unwrap_or(entries.len() - 1);
+            inspect.select(selected, entries);
+        }
+        KeyCode::Right => inspect.select((inspect.selected + 1) % entries.len(), entries),
+        // reload the current entry
+        KeyCode::Enter => inspect.select(inspect.selected, entries),
+        KeyCode::Up => inspect.scroll = inspect.scroll.saturating_sub(1),
+        KeyCode::Down => inspect.scroll = min(inspect.scroll + 1, inspect.max_scroll()),
+        KeyCode::PageUp => inspect.scroll = inspect.scroll.saturating_sub(inspect.page_height),
+        KeyCode::PageDown => {
+            inspect.scroll = min(inspect.scroll + inspect.page_height, inspect.max_scroll());
+        }
+        KeyCode::Home => inspect.scroll = 0,
+        KeyCode::End => inspect.scroll = inspect.max_scroll(),
+        KeyCode::Char('q') | KeyCode::Esc => {
+            stat.inspect = None;
+            stat.reset_input();
+        }
+        _ => {}
+    }
+}
+
+fn handle_color_mapping(code: KeyCode, tui_stat: &RwLock<TuiStat>) {
+    let mut stat = tui_stat.write().unwrap();
+    let Some(mapping) = stat.color_mapping.as_mut() else {
+        stat.reset_input();
+        return;
+    };
+    match code {
+        KeyCode::Char(c @ ('S' | 'M' | 'H' | 'T')) => {
+            mapping.target = ColorTarget::from_char(c).unwrap();
+        }
+        KeyCode::Char(c @ '0'..='7') => {
+            let n = c.to_digit(10).unwrap() as usize;
+            mapping.set_color(BASIC_COLORS[n].0);
+        }
+        KeyCode::Up => mapping.step_color(true),
+        KeyCode::Down => mapping.step_color(false),
+        KeyCode::Char('B') => {
+            mapping.scheme.bold = !mapping.scheme.bold;
+            mapping.theme = None;
+        }
+        KeyCode::Char('t') => mapping.next_theme(),
+        KeyCode::Char('z') => mapping.colorful = !mapping.colorful,
+        KeyCode::Enter => {
+            let mapping = *mapping;
+            stat.color_scheme = mapping.scheme;
+            stat.colorful = mapping.colorful;
+            stat.color_mapping = None;
+            stat.reset_input();
+        }
+        KeyCode::Char('q') | KeyCode::Esc => {
+            stat.color_mapping = None;
+            stat.reset_input();
+        }
+        _ => {}
+    }
+}
+
 fn handle_input_value(
     input_event: InputEvent,
     settings: &Settings,
@@ -585,6 +772,34 @@ fn handle_input_value(
             stat.reset_input();
             should_update.store(true, Ordering::Relaxed);
         }
+        InputEvent::InspectProc => {
+            let input_value = { tui_stat.read().unwrap().input_value.parse::<u32>() };
+            let mut stat = tui_stat.write().unwrap();
+            let pid = match input_value {
+                Ok(pid) => pid,
+                Err(_) if stat.input_value.trim().is_empty() => stat.selected_process.unwrap(),
+                Err(_) => {
+                    stat.reset_input();
+                    stat.input_message = Some(" Unacceptable integer ".into());
+                    should_update.store(true, Ordering::Relaxed);
+                    return;
+                }
+            };
+            stat.reset_input();
+            let exists = sysinfo()
+                .read()
+                .unwrap()
+                .process(Pid::from_u32(pid))
+                .is_some();
+            if !exists {
+                stat.input_message = Some(format!(" Unable to inspect pid '{pid}' "));
+                should_update.store(true, Ordering::Relaxed);
+                return;
+            }
+            stat.inspect = Some(Inspect::new(pid, &stat.inspect_entries));
+            stat.input_mode = InputMode::Inspect;
+            should_update.store(true, Ordering::Relaxed);
+        }
         #[cfg(target_os = "linux")]
         InputEvent::ReniceProc => {
             let input_value = { tui_stat.read().unwrap().input_value.parse::<u32>() };
@@ -609,11 +824,9 @@ fn handle_input_value(
         InputEvent::ReniceValue => {
             let mut stat = tui_stat.write().unwrap();
             let input_value = stat.input_value.parse::<i32>();
-            stat.input_mode = InputMode::Command;
+            let is_empty = stat.input_value.trim().is_empty();
             stat.reset_input();
             if input_value.is_err() || input_value.as_ref().is_ok_and(|v| *v < -20 || *v > 19) {
-                let is_empty = { tui_stat.read().unwrap().input_value.trim().is_empty() };
-                let mut stat = tui_stat.write().unwrap();
                 if !is_empty {
                     stat.input_message = Some(" Unacceptable nice value ".into());
                 }
@@ -621,6 +834,11 @@ fn handle_input_value(
                 return;
             }
             let input_value = input_value.unwrap();
+            if !stat.tagged.is_empty() {
+                confirm_tagged(&mut stat, data, TaggedAction::Renice(input_value));
+                should_update.store(true, Ordering::Relaxed);
+                return;
+            }
             let pid = stat.selected_process.unwrap();
             if let Err(e) = crate::action::renice(pid, input_value) {
                 stat.input_message = Some(format!(
@@ -657,17 +875,22 @@ fn handle_input_value(
         InputEvent::KillSignal => {
             use uucore::signals::signal_by_name_or_value;
             let mut stat = tui_stat.write().unwrap();
-            stat.input_mode = InputMode::Command;
+            let input_value = stat.input_value.trim().to_string();
             stat.reset_input();
-            let signal = if stat.input_value.is_empty() {
+            let signal = if input_value.is_empty() {
                 15
-            } else if let Some(sig) = signal_by_name_or_value(&stat.input_value) {
+            } else if let Some(sig) = signal_by_name_or_value(&input_value) {
                 sig
             } else {
                 stat.input_message = Some(" Unacceptable signal value".into());
                 should_update.store(true, Ordering::Relaxed);
                 return;
             };
+            if !stat.tagged.is_empty() {
+                confirm_tagged(&mut stat, data, TaggedAction::Signal(signal));
+                should_update.store(true, Ordering::Relaxed);
+                return;
+            }
             let pid = stat.selected_process.unwrap();
             if let Err(e) = crate::action::kill_process(pid, signal) {
                 stat.input_message = Some(format!(
@@ -677,5 +900,185 @@ fn handle_input_value(
             }
             should_update.store(true, Ordering::Relaxed);
         }
+        #[cfg(unix)]
+        InputEvent::ConfirmTagged => {
+            let mut stat = tui_stat.write().unwrap();
+            let confirmed = stat.input_value.trim().eq_ignore_ascii_case("y");
+            let action = stat.pending_action.take();
+            stat.reset_input();
+            match action {
+                Some(action) if confirmed => apply_tagged(&mut stat, data, action),
+                _ => data.write().unwrap().2 = None,
+            }
+            should_update.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+/// List the tagged tasks in the info bar and ask before `action` is applied to them.
+#[cfg(unix)]
+fn confirm_tagged(
+    stat: &mut TuiStat,
+    data: &RwLock<(Header, ProcList, Option<InfoBar>)>,
+    action: TaggedAction,
+) {
+    let (title, label) = match action {
+        #[cfg(target_os = "linux")]
+        TaggedAction::Renice(value) => (
+            format!("tasks to renice to {value}"),
+            format!("Renice these {} tasks to {value}? [y/N]", stat.tagged.len()),
+        ),
+        TaggedAction::Signal(signal) => (
+            format!("tasks to send signal {signal}"),
+            format!(
+                "Send these {} tasks signal {signal}? [y/N]",
+                stat.tagged.len()
+            ),
+        ),
+    };
+    let content = stat
+        .tagged
+        .keys()
+        .map(|pid| format!("{pid:>7} {}", get_command(*pid, false)))
+        .collect::<Vec<_>>()
+        .join("\n");
+    data.write().unwrap().2 = Some(InfoBar { title, content });
+
+    stat.pending_action = Some(action);
+    stat.input_label = label;
+    stat.input_mode = InputMode::Input(InputEvent::ConfirmTagged);
+}
+
+/// Apply `action` to every tagged task, failures are listed in the info bar.
+#[cfg(unix)]
+fn apply_tagged(
+    stat: &mut TuiStat,
+    data: &RwLock<(Header, ProcList, Option<InfoBar>)>,
+    action: TaggedAction,
+) {
+    let mut tagged = std::mem::take(&mut stat.tagged);
+    // a task may have exited since the last refresh, and its PID been reused
+    tagged.retain(|&pid, &mut started| start_time(pid) == Some(started));
+    let failures = tagged
+        .keys()
+        .filter_map(|pid| {
+            let result = match action {
+                #[cfg(target_os = "linux")]
+                TaggedAction::Renice(value) => crate::action::renice(*pid, value),
+                TaggedAction::Signal(signal) => crate::action::kill_process(*pid, signal),
+            };
+            result
+                .err()
+                .map(|e| format!("{pid:>7} {}: {e}", get_command(*pid, false)))
+        })
+        .collect::<Vec<_>>();
+
+    let what = match action {
+        #[cfg(target_os = "linux")]
+        TaggedAction::Renice(value) => format!("renice to {value}"),
+        TaggedAction::Signal(signal) => format!("signal {signal}"),
+    };
+    let mut data = data.write().unwrap();
+    if failures.is_empty() {
+        data.2 = None;
+        stat.input_message = Some(format!(" Applied {what} to {} tasks ", tagged.len()));
+    } else {
+        data.2 = Some(InfoBar {
+            title: format!(
+                "{what} failed for {} of {} tasks",
+                failures.len(),
+                tagged.len()
+            ),
+            content: failures.join("\n"),
+        });
+    }
+}
+
+/// Keys moving through a `--replay` recording, returns whether `e` was one of them.
+pub fn handle_replay_input(
+    e: &Event,
+    tui_stat: &RwLock<TuiStat>,
+    data: &RwLock<(Header, ProcList, Option<InfoBar>)>,
+    should_update: &AtomicBool,
+) -> bool {
+    {
+        let mut stat = tui_stat.write().unwrap();
+        if stat.input_mode != InputMode::Command {
+            return false;
+        }
+        let Some(replay) = stat.replay.as_mut() else {
+            return false;
+        };
+        let Event::Key(key) = e else {
+            return false;
+        };
+        let len = replay.recording.len() as isize;
+        let moved = match key.code {
+            KeyCode::Char('p') => {
+                replay.paused = !replay.paused;
+                false
+            }
+            KeyCode::Char(']') => replay.seek(1),
+            KeyCode::Char('[') => replay.seek(-1),
+            KeyCode::Char('}') => replay.seek(10),
+            KeyCode::Char('{') => replay.seek(-10),
+            KeyCode::Home => replay.seek(-len),
+            KeyCode::End => replay.seek(len),
+            _ => return false,
+        };
+        if moved {
+            // stepping through frames by hand
+            replay.paused = true;
+        }
+    }
+    load_replay_frame(tui_stat, data);
+    should_update.store(true, Ordering::Relaxed);
+    true
+}
+
+/// Whether `e` may be handled while replaying.
+///
+/// Commands that collect live data or act on live processes would mix them
+/// with the recording, so only display options are kept.
+pub fn replay_allows(e: &Event, tui_stat: &RwLock<TuiStat>) -> bool {
+    if tui_stat.read().unwrap().input_mode != InputMode::Command {
+        return true;
+    }
+    match e {
+        Event::Key(KeyEvent {
+            code: KeyCode::Char(c),
+            modifiers,
+            ..
+        }) => {
+            if modifiers.contains(KeyModifiers::CONTROL) {
+                *c == 'c'
+            } else {
+                "qbCdlmn#tWxXzZ4".contains(*c)
+            }
+        }
+        Event::Key(KeyEvent { code, .. }) => matches!(
+            code,
+            KeyCode::Up | KeyCode::Down | KeyCode::Left | KeyCode::Right
+        ),
+        _ => true,
+    }
+}
+
+/// Show the frame at the current position of the replay.
+pub fn load_replay_frame(
+    tui_stat: &RwLock<TuiStat>,
+    data: &RwLock<(Header, ProcList, Option<InfoBar>)>,
+) {
+    let mut stat = tui_stat.write().unwrap();
+    let Some(replay) = stat.replay.as_ref() else {
+        return;
+    };
+    match replay.recording.frame(replay.position) {
+        Ok((header, proc_list)) => {
+            let mut data = data.write().unwrap();
+            data.0 = header;
+            data.1 = proc_list;
+        }
+        Err(e) => stat.input_message = Some(format!(" Broken frame: {e} ")),
     }
 }
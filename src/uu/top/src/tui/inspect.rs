@@ -0,0 +1,251 @@
+// This file is part of the uutils procps package.
+//
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+use std::fs;
+use std::io::{self, Read};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum InspectSource {
+    File(String),
+    Pipe(String),
+    /// Listing of `/proc/<pid>/fd` with the link targets
+    FileDescriptors,
+}
+
+/// One of the entries selectable on the inspect screen.
+///
+/// `%d` in paths and commands is replaced by the PID.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct InspectEntry {
+    pub name: String,
+    pub source: InspectSource,
+}
+
+impl InspectEntry {
+    fn new(name: &str, source: InspectSource) -> Self {
+        Self {
+            name: name.into(),
+            source,
+        }
+    }
+
+    /// Commands are started in the background, as they may take a while.
+    pub fn load(&self, pid: u32) -> Content {
+        let pid = pid.to_string();
+        Content::Ready(match &self.source {
+            InspectSource::File(path) => {
+                let path = path.replace("%d", &pid);
+                match fs::read(&path) {
+                    // environ and cmdline are separated by NUL
+                    Ok(content) => String::from_utf8_lossy(&content).replace('\0', "\n"),
+                    Err(e) => format!("Failed to read {path}: {e}"),
+                }
+            }
+            InspectSource::Pipe(command) => {
+                let command = command.replace("%d", &pid);
+                return match Running::start(&command) {
+                    Ok(running) => Content::Running(command, running),
+                    Err(e) => Content::Ready(format!("Failed to run {command}: {e}")),
+                };
+            }
+            InspectSource::FileDescriptors => {
+                let path = format!("/proc/{pid}/fd");
+                let entries = match fs::read_dir(&path) {
+                    Ok(entries) => entries,
+                    Err(e) => return Content::Ready(format!("Failed to read {path}: {e}")),
+                };
+                let mut fds = entries
+                    .flatten()
+                    .filter_map(|entry| {
+                        let fd = entry.file_name().to_str()?.parse::<u32>().ok()?;
+                        let target = fs::read_link(entry.path())
+                            .map(|it| it.display().to_string())
+                            .unwrap_or_else(|e| e.to_string());
+                        Some((fd, target))
+                    })
+                    .collect::<Vec<_>>();
+                fds.sort();
+                fds.into_iter()
+                    .map(|(fd, target)| format!("{fd:>5} -> {target}\n"))
+                    .collect()
+            }
+        })
+    }
+}
+
+pub(crate) enum Content {
+    Ready(String),
+    /// The command, and its run
+    Running(String, Running),
+}
+
+/// A command of the inspect screen running in the background, killed if it is
+/// still running when dropped.
+pub(crate) struct Running {
+    child: Child,
+    /// Set once it finished
+    output: Arc<Mutex<Option<String>>>,
+}
+
+impl Running {
+    fn start(command: &str) -> io::Result<Self> {
+        let mut command_line = Command::new("sh");
+        // Both outputs in the one pipe, reading them in turn could block
+        command_line
+            .arg("-c")
+            .arg(format!("exec 2>&1; {command}"))
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped());
+        // To be killed with the whole pipeline
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut command_line, 0);
+        let mut child = command_line.spawn()?;
+        let mut stdout = child.stdout.take().unwrap();
+        let output = Arc::new(Mutex::new(None));
+        let result = output.clone();
+        thread::spawn(move || {
+            let mut content = Vec::new();
+            let _ = stdout.read_to_end(&mut content);
+            *result.lock().unwrap() = Some(String::from_utf8_lossy(&content).into_owned());
+        });
+        Ok(Self { child, output })
+    }
+}
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        // Harmless once it exited, it is reaped either way
+        #[cfg(unix)]
+        {
+            use rustix::process::{kill_process_group, Pid, Signal};
+            if let Some(pid) = Pid::from_raw(self.child.id() as i32) {
+                let _ = kill_process_group(pid, Signal::KILL);
+            }
+        }
+        #[cfg(not(unix))]
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+pub(crate) fn default_entries() -> Vec<InspectEntry> {
+    vec![
+        InspectEntry::new("Status", InspectSource::File("/proc/%d/status".into())),
+        InspectEntry::new(
+            "Memory",
+            InspectSource::File("/proc/%d/smaps_rollup".into()),
+        ),
+        InspectEntry::new("Limits", InspectSource::File("/proc/%d/limits".into())),
+        InspectEntry::new("Open Files", InspectSource::FileDescriptors),
+        InspectEntry::new(
+            "Environment",
+            InspectSource::File("/proc/%d/environ".into()),
+        ),
+    ]
+}
+
+/// State of the inspect screen opened with 'Y'.
+pub(crate) struct Inspect {
+    pub pid: u32,
+    pub selected: usize,
+    pub content: String,
+    pub scroll: usize,
+    pub page_height: usize, // updated while rendering
+    /// The command of the selected entry, while it runs
+    running: Option<Running>,
+}
+
+impl Inspect {
+    pub fn new(pid: u32, entries: &[InspectEntry]) -> Self {
+        let mut inspect = Self {
+            pid,
+            selected: 0,
+            content: String::new(),
+            scroll: 0,
+            page_height: 1,
+            running: None,
+        };
+        inspect.select(0, entries);
+        inspect
+    }
+
+    pub fn select(&mut self, selected: usize, entries: &[InspectEntry]) {
+        self.selected = selected;
+        self.scroll = 0;
+        // Whatever was running is of no use anymore
+        self.running = None;
+        self.content = match entries.get(selected).map(|entry| entry.load(self.pid)) {
+            Some(Content::Ready(content)) => content,
+            Some(Content::Running(command, running)) => {
+                self.running = Some(running);
+                format!("Running {command}...")
+            }
+            None => String::new(),
+        };
+    }
+
+    /// Show the output of the command once it finished, returns whether the
+    /// content changed.
+    pub fn poll(&mut self) -> bool {
+        let Some(output) = self
+            .running
+            .as_ref()
+            .and_then(|running| running.output.lock().unwrap().take())
+        else {
+            return false;
+        };
+        self.content = output;
+        self.running = None;
+        true
+    }
+
+    pub fn max_scroll(&self) -> usize {
+        self.content.lines().count().saturating_sub(1)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn pipe(command: &str) -> Vec<InspectEntry> {
+        vec![InspectEntry::new(
+            "Pipe",
+            InspectSource::Pipe(command.into()),
+        )]
+    }
+
+    #[test]
+    fn test_pipe_in_background() {
+        let entries = pipe("sleep 0.2; echo out %d; echo err >&2");
+        let start = Instant::now();
+        let mut inspect = Inspect::new(42, &entries);
+        assert!(start.elapsed() < Duration::from_millis(200));
+        assert_eq!(
+            inspect.content,
+            "Running sleep 0.2; echo out 42; echo err >&2..."
+        );
+
+        while !inspect.poll() {
+            assert!(start.elapsed() < Duration::from_secs(5));
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(inspect.content, "out 42\nerr\n");
+        assert!(!inspect.poll());
+    }
+
+    #[test]
+    fn test_pipe_cancelled() {
+        let entries = pipe("sleep 10");
+        let start = Instant::now();
+        // Leaving the screen kills the command
+        drop(Inspect::new(1, &entries));
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
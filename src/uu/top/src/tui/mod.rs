@@ -3,15 +3,16 @@
 // For the full copyright and license information, please view the LICENSE
 // file that was distributed with this source code.
 
-mod color;
+pub(crate) mod color;
 mod input;
+pub(crate) mod inspect;
 pub mod stat;
 
 pub use input::*;
 use std::borrow::Cow;
 
 use crate::header::{format_memory, Header};
-use crate::tui::color::TuiColorHelper;
+use crate::tui::color::{color_name, color_number, TuiColorHelper, BASIC_COLORS};
 use crate::tui::stat::{CpuGraphMode, MemoryGraphMode, TuiStat};
 use crate::{InfoBar, ProcList};
 use ratatui::prelude::*;
@@ -101,7 +102,7 @@ impl<'a> Tui<'a> {
 
     fn render_header(&self, area: Rect, buf: &mut Buffer) {
         let constraints = vec![Constraint::Length(1); self.calc_header_height() as usize];
-        let colorful = self.stat.colorful;
+        let colors = self.stat.colors();
 
         let cpu = &self.header.cpu;
 
@@ -138,7 +139,7 @@ impl<'a> Tui<'a> {
                 if i_columns > 0 {
                     Line::from(vec![
                         Span::raw(" "),
-                        Span::styled(" ", Style::default().bg_secondary(colorful)),
+                        Span::styled(" ", Style::default().bg_secondary(colors)),
                         Span::raw(" "),
                     ])
                     .render(cpu_column.as_ref().unwrap()[column_offset - 1], buf);
@@ -156,7 +157,7 @@ impl<'a> Tui<'a> {
                 .split(area);
                 i_columns += 1;
 
-                Span::styled(format!("%{tag:<6}:",), Style::default().primary(colorful))
+                Span::styled(format!("%{tag:<6}:",), Style::default().primary(colors))
                     .render(line_layout[0], buf);
                 let percentage = if print_percentage {
                     format!("{:>5.0}", ((red + yellow) * 100.0).round())
@@ -167,7 +168,7 @@ impl<'a> Tui<'a> {
                     Span::raw(format!("{l:>5.1}")),
                     Span::styled(
                         format!("/{r:<5.1}{percentage}"),
-                        Style::default().primary(colorful),
+                        Style::default().primary(colors),
                     ),
                 ])
                 .render(line_layout[1], buf);
@@ -180,17 +181,17 @@ impl<'a> Tui<'a> {
                 let red_span = Span::styled(
                     content.to_string().repeat(red_width as usize),
                     if content == ' ' {
-                        Style::default().bg_primary(colorful)
+                        Style::default().bg_primary(colors)
                     } else {
-                        Style::default().primary(colorful)
+                        Style::default().primary(colors)
                     },
                 );
                 let yellow_span = Span::styled(
                     content.to_string().repeat(yellow_width as usize),
                     if content == ' ' {
-                        Style::default().bg_secondary(colorful)
+                        Style::default().bg_secondary(colors)
                     } else {
-                        Style::default().secondary(colorful)
+                        Style::default().secondary(colors)
                     },
                 );
 
@@ -216,17 +217,17 @@ impl<'a> Tui<'a> {
         if self.stat.cpu_graph_mode != CpuGraphMode::Hide {
             let task = &self.header.task;
             let task_line = vec![
-                Span::styled("Tasks: ", Style::default().primary(colorful)),
+                Span::styled("Tasks: ", Style::default().primary(colors)),
                 Span::raw(task.total.to_string()),
-                Span::styled(" total, ", Style::default().primary(colorful)),
+                Span::styled(" total, ", Style::default().primary(colors)),
                 Span::raw(task.running.to_string()),
-                Span::styled(" running, ", Style::default().primary(colorful)),
+                Span::styled(" running, ", Style::default().primary(colors)),
                 Span::raw(task.sleeping.to_string()),
-                Span::styled(" sleeping, ", Style::default().primary(colorful)),
+                Span::styled(" sleeping, ", Style::default().primary(colors)),
                 Span::raw(task.stopped.to_string()),
-                Span::styled(" stopped, ", Style::default().primary(colorful)),
+                Span::styled(" stopped, ", Style::default().primary(colors)),
                 Span::raw(task.zombie.to_string()),
-                Span::styled(" zombie", Style::default().primary(colorful)),
+                Span::styled(" zombie", Style::default().primary(colors)),
             ];
             Line::from(task_line).render(header_layout[i], buf);
             i += 1;
@@ -241,23 +242,23 @@ impl<'a> Tui<'a> {
             for (tag, load) in cpu {
                 if self.stat.cpu_graph_mode == CpuGraphMode::Sum {
                     Line::from(vec![
-                        Span::styled(format!("%{tag:<6}:  ",), Style::default().red()),
+                        Span::styled(format!("%{tag:<6}:  ",), Style::default().primary(colors)),
                         Span::raw(format!("{:.1}", load.user)),
-                        Span::styled(" us, ", Style::default().red()),
+                        Span::styled(" us, ", Style::default().primary(colors)),
                         Span::raw(format!("{:.1}", load.system)),
-                        Span::styled(" sy, ", Style::default().red()),
+                        Span::styled(" sy, ", Style::default().primary(colors)),
                         Span::raw(format!("{:.1}", load.nice)),
-                        Span::styled(" ni, ", Style::default().red()),
+                        Span::styled(" ni, ", Style::default().primary(colors)),
                         Span::raw(format!("{:.1}", load.idle)),
-                        Span::styled(" id, ", Style::default().red()),
+                        Span::styled(" id, ", Style::default().primary(colors)),
                         Span::raw(format!("{:.1}", load.io_wait)),
-                        Span::styled(" wa, ", Style::default().red()),
+                        Span::styled(" wa, ", Style::default().primary(colors)),
                         Span::raw(format!("{:.1}", load.hardware_interrupt)),
-                        Span::styled(" hi, ", Style::default().red()),
+                        Span::styled(" hi, ", Style::default().primary(colors)),
                         Span::raw(format!("{:.1}", load.software_interrupt)),
-                        Span::styled(" si, ", Style::default().red()),
+                        Span::styled(" si, ", Style::default().primary(colors)),
                         Span::raw(format!("{:.1}", load.steal_time)),
-                        Span::styled(" st", Style::default().red()),
+                        Span::styled(" st", Style::default().primary(colors)),
                     ])
                     .render(header_layout[i], buf);
                     i += 1;
@@ -297,32 +298,32 @@ impl<'a> Tui<'a> {
                 Line::from(vec![
                     Span::styled(
                         format!("{unit_name} Mem : "),
-                        Style::default().primary(colorful),
+                        Style::default().primary(colors),
                     ),
                     Span::raw(format!("{:8.1}", format_memory(mem.total, unit))),
-                    Span::styled(" total, ", Style::default().primary(colorful)),
+                    Span::styled(" total, ", Style::default().primary(colors)),
                     Span::raw(format!("{:8.1}", format_memory(mem.free, unit))),
-                    Span::styled(" free, ", Style::default().primary(colorful)),
+                    Span::styled(" free, ", Style::default().primary(colors)),
                     Span::raw(format!("{:8.1}", format_memory(mem.used, unit))),
-                    Span::styled(" used, ", Style::default().primary(colorful)),
+                    Span::styled(" used, ", Style::default().primary(colors)),
                     Span::raw(format!("{:8.1}", format_memory(mem.buff_cache, unit))),
-                    Span::styled(" buff/cache", Style::default().primary(colorful)),
+                    Span::styled(" buff/cache", Style::default().primary(colors)),
                 ])
                 .render(header_layout[i], buf);
                 i += 1;
                 Line::from(vec![
                     Span::styled(
                         format!("{unit_name} Swap: "),
-                        Style::default().primary(colorful),
+                        Style::default().primary(colors),
                     ),
                     Span::raw(format!("{:8.1}", format_memory(mem.total_swap, unit))),
-                    Span::styled(" total, ", Style::default().primary(colorful)),
+                    Span::styled(" total, ", Style::default().primary(colors)),
                     Span::raw(format!("{:8.1}", format_memory(mem.free_swap, unit))),
-                    Span::styled(" free, ", Style::default().primary(colorful)),
+                    Span::styled(" free, ", Style::default().primary(colors)),
                     Span::raw(format!("{:8.1}", format_memory(mem.used_swap, unit))),
-                    Span::styled(" used, ", Style::default().primary(colorful)),
+                    Span::styled(" used, ", Style::default().primary(colors)),
                     Span::raw(format!("{:8.1}", format_memory(mem.available, unit))),
-                    Span::styled(" avail Mem", Style::default().primary(colorful)),
+                    Span::styled(" avail Mem", Style::default().primary(colors)),
                 ])
                 .render(header_layout[i], buf);
             } else {
@@ -369,7 +370,7 @@ impl<'a> Tui<'a> {
     }
 
     fn render_input(&self, area: Rect, buf: &mut Buffer) {
-        let colorful = self.stat.colorful;
+        let colors = self.stat.colors();
         if let Some(v) = self.stat.input_message.as_ref() {
             let layout = Layout::new(
                 Direction::Horizontal,
@@ -377,16 +378,18 @@ impl<'a> Tui<'a> {
             )
             .split(area);
             Paragraph::new(v.as_str())
-                .style(Style::default().error(colorful))
+                .style(Style::default().error(colors))
                 .render(layout[0], buf);
             return;
         }
         let input = if !self.stat.input_label.is_empty() || !self.stat.input_value.is_empty() {
             Line::from(vec![
-                Span::styled(&self.stat.input_label, Style::default().primary(colorful)),
+                Span::styled(&self.stat.input_label, Style::default().primary(colors)),
                 Span::raw(" "),
                 Span::raw(&self.stat.input_value),
             ])
+        } else if let Some(replay) = &self.stat.replay {
+            Line::from(replay.status())
         } else if self.stat.show_coordinates {
             let list_coordinates = self.calc_list_coordinates();
             let column_coordinates = self.calc_column_coordinates();
@@ -411,7 +414,7 @@ impl<'a> Tui<'a> {
     }
 
     fn render_list(&mut self, area: Rect, buf: &mut Buffer) {
-        let colorful = self.stat.colorful;
+        let colors = self.stat.colors();
         let highlight_sorted = self.stat.highlight_sorted;
         let highlight_bold = self.stat.highlight_bold;
         let sorter = if self.stat.sort_by_pid {
@@ -475,7 +478,7 @@ impl<'a> Tui<'a> {
                 .clone()
                 .split_off(column_coordinates.0),
         )
-        .style(Style::default().bg_secondary(colorful));
+        .style(Style::default().bg_secondary(colors));
 
         let rows = self.proc_list.collected.iter().map(|item| {
             let cells = item
@@ -505,16 +508,21 @@ impl<'a> Tui<'a> {
                         Cell::from(Span::styled(
                             c,
                             if highlight_bold {
-                                Style::default().bg_primary(colorful)
+                                Style::default().bg_primary(colors)
                             } else {
-                                Style::default().primary(colorful)
+                                Style::default().primary(colors)
                             },
                         ))
                     } else {
                         Cell::from(c)
                     }
                 });
-            Row::new(cells).height(1)
+            let style = if self.stat.tagged.contains_key(&item.0) {
+                Style::default().bg_primary(colors)
+            } else {
+                Style::default().task(colors)
+            };
+            Row::new(cells).height(1).style(style)
         });
 
         let mut state = TableState::default().with_offset(list_coordinates.0);
@@ -529,7 +537,7 @@ impl<'a> Tui<'a> {
             let layout = Layout::new(Direction::Vertical, constraints).split(area);
             Line::from(Span::styled(
                 format!("{:<width$}", info_bar.title, width = area.width as usize),
-                Style::default().bg_secondary(self.stat.colorful),
+                Style::default().bg_secondary(self.stat.colors()),
             ))
             .render(layout[0], buf);
             let mut lines = vec![];
@@ -546,6 +554,106 @@ impl<'a> Tui<'a> {
             Paragraph::new(lines).render(layout[1], buf);
         }
     }
+
+    fn render_color_mapping(&self, area: Rect, buf: &mut Buffer) {
+        let Some(mapping) = self.stat.color_mapping.as_ref() else {
+            return;
+        };
+        let colors = self.stat.colors();
+        let width = area.width as usize;
+
+        let sample_task = self
+            .proc_list
+            .collected
+            .first()
+            .map(|(_, columns)| columns.join(" "))
+            .unwrap_or_default();
+        let theme = mapping.theme.map(|t| t.name()).unwrap_or("custom");
+        let color = mapping.scheme.get(mapping.target);
+        let color = match color_number(color) {
+            Some(n) => format!("{n} ({})", color_name(color)),
+            None => color_name(color),
+        };
+
+        let mut lines = vec![
+            Line::from(format!(
+                "Help for color mapping - current window: 1:Def, theme: {theme}"
+            )),
+            Line::from(""),
+            Line::from(Span::styled(
+                format!("{:<width$}", self.proc_list.fields.join(" ")),
+                Style::default().bg_secondary(colors),
+            )),
+            Line::from(Span::styled(sample_task, Style::default().task(colors))),
+            Line::from(Span::styled(
+                " Message sample ",
+                Style::default().error(colors),
+            )),
+            Line::from(""),
+            Line::from(format!(
+                "   1) Select a target as an upper case letter, current target is  {} :",
+                mapping.target.as_char()
+            )),
+            Line::from("         S = Summary Data,  M = Messages/Prompts,"),
+            Line::from("         H = Column Heads,  T = Task Information"),
+            Line::from("   2) Select a color as a number or use the up/down arrow keys"),
+            Line::from(format!(
+                "         to raise/lower the 0-7 color value, current color is {color} :"
+            )),
+        ];
+        lines.extend(BASIC_COLORS.chunks(4).enumerate().map(|(row, chunk)| {
+            let entries = chunk
+                .iter()
+                .enumerate()
+                .map(|(n, (_, name))| format!("{} = {name}", row * 4 + n))
+                .collect::<Vec<_>>()
+                .join(",  ");
+            Line::from(format!("         {entries}"))
+        }));
+        lines.extend([
+            Line::from("   3) Adjust the whole window with these keys:"),
+            Line::from(format!(
+                "         't' to cycle the built-in themes, 'B' bold is {}, 'z' color is {}",
+                if mapping.scheme.bold { "On" } else { "Off" },
+                if mapping.colorful { "On" } else { "Off" },
+            )),
+            Line::from("   4) Then use these keys when finished:"),
+            Line::from("         'q' or <Esc> to abort changes to window '1:Def'"),
+            Line::from("         <Enter> to commit and end, 'W' afterwards to save them"),
+        ]);
+        Paragraph::new(lines).render(area, buf);
+    }
+
+    fn render_inspect(&mut self, area: Rect, buf: &mut Buffer) {
+        let colors = self.stat.colors();
+        let entries = &self.stat.inspect_entries;
+        let Some(inspect) = self.stat.inspect.as_mut() else {
+            return;
+        };
+        let layout = Layout::new(
+            Direction::Vertical,
+            [Constraint::Length(1), Constraint::Min(0)],
+        )
+        .split(area);
+
+        let mut tabs = vec![Span::raw(format!("Inspect pid {}: ", inspect.pid))];
+        for (n, entry) in entries.iter().enumerate() {
+            let name = format!(" {} ", entry.name);
+            if n == inspect.selected {
+                tabs.push(Span::styled(name, Style::default().bg_secondary(colors)));
+            } else {
+                tabs.push(Span::raw(name));
+            }
+        }
+        Line::from(tabs).render(layout[0], buf);
+
+        inspect.page_height = layout[1].height.max(1) as usize;
+        inspect.scroll = min(inspect.scroll, inspect.max_scroll());
+        Paragraph::new(inspect.content.as_str())
+            .style(Style::default().task(colors))
+            .scroll((inspect.scroll as u16, 0))
+            .render(layout[1], buf);
+    }
 }
 
 impl Widget for Tui<'_> {
@@ -567,6 +675,14 @@ impl Widget for Tui<'_> {
 
         self.render_header(layout[0], buf);
         self.render_input(layout[1], buf);
+        if self.stat.color_mapping.is_some() {
+            self.render_color_mapping(layout[2], buf);
+            return;
+        }
+        if self.stat.inspect.is_some() {
+            self.render_inspect(layout[2], buf);
+            return;
+        }
         let mut list_area = layout[2];
         if self.stat.max_list_display > 0 {
             let list_height = min(layout[2].height, self.stat.max_list_display as u16) + 1; // 1 for header
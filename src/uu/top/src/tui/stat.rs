@@ -3,8 +3,12 @@
 // For the full copyright and license information, please view the LICENSE
 // file that was distributed with this source code.
 
+use crate::record::Replay;
 use crate::selected_fields;
-use crate::tui::input::InputMode;
+use crate::tui::color::{ColorMapping, ColorScheme};
+use crate::tui::input::{InputMode, TaggedAction};
+use crate::tui::inspect::{default_entries, Inspect, InspectEntry};
+use std::collections::BTreeMap;
 use std::time::Duration;
 
 pub(crate) struct TuiStat {
@@ -14,6 +18,9 @@ pub(crate) struct TuiStat {
     pub input_message: Option<String>, // Info or error
     #[allow(dead_code)]
     pub selected_process: Option<u32>,
+    /// By PID, the start time of the tagged task
+    pub tagged: BTreeMap<u32, u64>,
+    pub pending_action: Option<TaggedAction>,
 
     pub show_load_avg: bool,
     pub cpu_graph_mode: CpuGraphMode,
@@ -24,6 +31,8 @@ pub(crate) struct TuiStat {
     pub horizontal_offset: usize,
     pub max_list_display: usize,
     pub colorful: bool,
+    pub color_scheme: ColorScheme,
+    pub color_mapping: Option<ColorMapping>, // pending changes of the 'Z' screen
     pub full_command_line: bool,
     pub delay: Duration,
     pub sorter: String,
@@ -35,6 +44,10 @@ pub(crate) struct TuiStat {
     pub irix_mode: bool,
     pub width_increment: Option<usize>, // None means auto
     pub time_scale: TimeScale,
+    pub cumulative_time: bool,
+    pub inspect: Option<Inspect>,
+    pub inspect_entries: Vec<InspectEntry>,
+    pub replay: Option<Replay>,
 
     pub filter: Option<crate::Filter>,
 }
@@ -54,6 +67,8 @@ impl TuiStat {
             input_value: String::new(),
             input_message: None,
             selected_process: None,
+            tagged: BTreeMap::new(),
+            pending_action: None,
 
             show_load_avg: true,
             cpu_graph_mode: CpuGraphMode::default(),
@@ -64,6 +79,8 @@ impl TuiStat {
             horizontal_offset: 0,
             max_list_display: 0, // unlimited
             colorful: true,
+            color_scheme: ColorScheme::default(),
+            color_mapping: None,
             full_command_line: true,
             delay: Duration::from_millis(1500), // 1.5s
             sorter: filter,
@@ -75,6 +92,10 @@ impl TuiStat {
             irix_mode: true,
             width_increment: Some(0), // fixed
             time_scale: TimeScale::default(),
+            cumulative_time: false,
+            inspect: None,
+            inspect_entries: default_entries(),
+            replay: None,
 
             filter: None,
         }
@@ -86,6 +107,16 @@ impl TuiStat {
         self.input_value.clear();
         self.input_message = None;
     }
+
+    /// Colors to render with, `None` if colors are turned off.
+    ///
+    /// While the 'Z' screen is open, its pending changes are previewed.
+    pub fn colors(&self) -> Option<ColorScheme> {
+        match &self.color_mapping {
+            Some(mapping) => mapping.colorful.then_some(mapping.scheme),
+            None => self.colorful.then_some(self.color_scheme),
+        }
+    }
 }
 
 #[derive(Debug, Default, PartialEq)]
@@ -0,0 +1,232 @@
+// This file is part of the uutils procps package.
+//
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+use ratatui::style::{Color, Modifier, Style};
+
+const ESC: char = '\x1b';
+const BEL: char = '\x07';
+
+/// The 8 colors and their bright variants, in SGR order
+const BASIC_COLORS: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Gray,
+    Color::DarkGray,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::White,
+];
+
+/// A line of the output, as the characters to put in the cells with their style.
+pub(crate) type StyledLine = Vec<(char, Style)>;
+
+/// Split the output into lines of styled characters.
+///
+/// With `color`, SGR sequences (`ESC [ ... m`) set the style of the characters
+/// that follow, like they do in a terminal. Every other escape sequence and
+/// control character but the tab is dropped, so that the output can't move
+/// the cursor around or otherwise mess up the screen.
+pub(crate) fn parse(output: &str, color: bool) -> Vec<StyledLine> {
+    let mut lines = Vec::new();
+    let mut line = StyledLine::new();
+    let mut style = Style::default();
+    let mut chars = output.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => lines.push(std::mem::take(&mut line)),
+            '\t' => line.push((c, style)),
+            // A stray ESC must not swallow the end of the line, hence the peeking
+            ESC => match chars.peek() {
+                // CSI: parameters and intermediates up to a final byte in '@'..='~'
+                Some('[') => {
+                    chars.next();
+                    let mut params = String::new();
+                    while let Some(c) = chars.next_if(|c| !c.is_control()) {
+                        if ('@'..='~').contains(&c) {
+                            if c == 'm' && color {
+                                style = apply_sgr(style, &params);
+                            }
+                            break;
+                        }
+                        params.push(c);
+                    }
+                }
+                // OSC, DCS and friends: a string terminated by BEL or ST
+                Some(']' | 'P' | 'X' | '^' | '_') => {
+                    while let Some(c) = chars.next() {
+                        if c == BEL || (c == ESC && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                // Character set designation, like `ESC ( B`
+                Some('(' | ')' | '*' | '+') => {
+                    chars.next();
+                    chars.next_if(|c| !c.is_control());
+                }
+                // Two character sequences, like `ESC 7` to save the cursor
+                Some(c) if !c.is_control() => {
+                    chars.next();
+                }
+                _ => {}
+            },
+            c if c.is_control() => {}
+            c => line.push((c, style)),
+        }
+    }
+
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+fn apply_sgr(mut style: Style, params: &str) -> Style {
+    // Empty and unparsable parameters are treated as 0, like terminals do
+    let mut codes = params
+        .split([';', ':'])
+        .map(|code| code.parse::<u16>().unwrap_or(0));
+
+    while let Some(code) = codes.next() {
+        style = match code {
+            0 => Style::default(),
+            1 => style.add_modifier(Modifier::BOLD),
+            2 => style.add_modifier(Modifier::DIM),
+            3 => style.add_modifier(Modifier::ITALIC),
+            4 => style.add_modifier(Modifier::UNDERLINED),
+            5 => style.add_modifier(Modifier::SLOW_BLINK),
+            6 => style.add_modifier(Modifier::RAPID_BLINK),
+            7 => style.add_modifier(Modifier::REVERSED),
+            8 => style.add_modifier(Modifier::HIDDEN),
+            9 => style.add_modifier(Modifier::CROSSED_OUT),
+            21 | 22 => without(style, Modifier::BOLD | Modifier::DIM),
+            23 => without(style, Modifier::ITALIC),
+            24 => without(style, Modifier::UNDERLINED),
+            25 => without(style, Modifier::SLOW_BLINK | Modifier::RAPID_BLINK),
+            27 => without(style, Modifier::REVERSED),
+            28 => without(style, Modifier::HIDDEN),
+            29 => without(style, Modifier::CROSSED_OUT),
+            30..=37 => style.fg(BASIC_COLORS[(code - 30) as usize]),
+            38 => match extended_color(&mut codes) {
+                Some(color) => style.fg(color),
+                None => style,
+            },
+            39 => style.fg(Color::Reset),
+            40..=47 => style.bg(BASIC_COLORS[(code - 40) as usize]),
+            48 => match extended_color(&mut codes) {
+                Some(color) => style.bg(color),
+                None => style,
+            },
+            49 => style.bg(Color::Reset),
+            90..=97 => style.fg(BASIC_COLORS[(code - 90 + 8) as usize]),
+            100..=107 => style.bg(BASIC_COLORS[(code - 100 + 8) as usize]),
+            _ => style,
+        };
+    }
+    style
+}
+
+/// Unlike `Style::remove_modifier`, this doesn't record the modifier as one to
+/// remove: the cells are drawn from a reset state anyway.
+fn without(mut style: Style, modifier: Modifier) -> Style {
+    style.add_modifier.remove(modifier);
+    style
+}
+
+/// The color of `38;5;n` (256 colors) or `38;2;r;g;b` (true color), after the 38.
+fn extended_color(codes: &mut impl Iterator<Item = u16>) -> Option<Color> {
+    let mut component = || codes.next().and_then(|code| u8::try_from(code).ok());
+    match component() {
+        Some(5) => component().map(Color::Indexed),
+        Some(2) => Some(Color::Rgb(component()?, component()?, component()?)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(line: &StyledLine) -> String {
+        line.iter().map(|(c, _)| c).collect()
+    }
+
+    #[test]
+    fn test_plain() {
+        let lines = parse("one\ntwo\tcolumns\n\nlast", true);
+        assert_eq!(
+            lines.iter().map(text).collect::<Vec<_>>(),
+            ["one", "two\tcolumns", "", "last"]
+        );
+        assert!(lines
+            .iter()
+            .flatten()
+            .all(|(_, style)| *style == Style::default()));
+    }
+
+    #[test]
+    fn test_colors() {
+        let lines = parse(
+            "\x1b[1;31mred\x1b[0m \x1b[38;5;208mo\x1b[48;2;1;2;3mt\x1b[mx\x1b[94mb",
+            true,
+        );
+        let line = &lines[0];
+        assert_eq!(text(line), "red otxb");
+        assert_eq!(
+            line[0].1,
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+        );
+        assert_eq!(line[3].1, Style::default());
+        assert_eq!(line[4].1, Style::default().fg(Color::Indexed(208)));
+        assert_eq!(
+            line[5].1,
+            Style::default()
+                .fg(Color::Indexed(208))
+                .bg(Color::Rgb(1, 2, 3))
+        );
+        assert_eq!(line[6].1, Style::default());
+        assert_eq!(line[7].1, Style::default().fg(Color::LightBlue));
+    }
+
+    #[test]
+    fn test_style_spans_lines() {
+        let lines = parse("\x1b[4;7mfirst\nsecond\x1b[24m!", true);
+        let expected = Style::default().add_modifier(Modifier::UNDERLINED | Modifier::REVERSED);
+        assert_eq!(lines[1][0].1, expected);
+        assert_eq!(
+            lines[1][6].1,
+            Style::default().add_modifier(Modifier::REVERSED)
+        );
+    }
+
+    #[test]
+    fn test_without_color() {
+        let lines = parse("\x1b[31mred\x1b[0m", false);
+        assert_eq!(text(&lines[0]), "red");
+        assert_eq!(lines[0][0].1, Style::default());
+    }
+
+    #[test]
+    fn test_other_sequences_dropped() {
+        let lines = parse(
+            "\x1b[2J\x1b[1;1Hclear\x1b]0;title\x07ed\x1b(B\r\x08!\x1b]8;;url\x1b\\\x1b\nnext",
+            true,
+        );
+        assert_eq!(
+            lines.iter().map(text).collect::<Vec<_>>(),
+            ["cleared!", "next"]
+        );
+    }
+}
@@ -0,0 +1,86 @@
+// This file is part of the uutils procps package.
+//
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+use jiff::Zoned;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
+use uucore::display::Quotable;
+use uucore::error::{strip_errno, UError, UResult, USimpleError};
+
+/// Keeps the outputs that differ from the previous one, each in a file of its
+/// own in `--output-dir` and/or appended to the `--log` file.
+pub(crate) struct Recorder {
+    output_dir: Option<PathBuf>,
+    log: Option<(PathBuf, File)>,
+}
+
+impl Recorder {
+    pub fn new(output_dir: Option<&Path>, log: Option<&Path>) -> UResult<Self> {
+        if let Some(dir) = output_dir {
+            fs::create_dir_all(dir).map_err(|e| {
+                USimpleError::new(
+                    1,
+                    format!(
+                        "cannot create directory {}: {}",
+                        dir.quote(),
+                        strip_errno(&e)
+                    ),
+                )
+            })?;
+        }
+        let log = match log {
+            Some(path) => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .map_err(|e| cannot_write(path, &e))?;
+                Some((path.to_path_buf(), file))
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            output_dir: output_dir.map(Path::to_path_buf),
+            log,
+        })
+    }
+
+    pub fn record(
+        &mut self,
+        number: u64,
+        time: &Zoned,
+        status: ExitStatus,
+        output: &str,
+    ) -> UResult<()> {
+        if let Some(dir) = &self.output_dir {
+            // The run number keeps the names unique, and in order
+            let path = dir.join(format!("{}-{number}.txt", time.strftime("%Y%m%d-%H%M%S")));
+            fs::write(&path, output).map_err(|e| cannot_write(&path, &e))?;
+        }
+
+        if let Some((path, file)) = &mut self.log {
+            let mut entry = format!(
+                "==== {}, {status} ====\n{output}",
+                time.strftime("%a %b %e %H:%M:%S %Y")
+            );
+            if !entry.ends_with('\n') {
+                entry.push('\n');
+            }
+            file.write_all(entry.as_bytes())
+                .map_err(|e| cannot_write(path, &e))?;
+        }
+        Ok(())
+    }
+}
+
+fn cannot_write(path: &Path, e: &std::io::Error) -> Box<dyn UError> {
+    USimpleError::new(
+        1,
+        format!("cannot write to {}: {}", path.quote(), strip_errno(e)),
+    )
+}
@@ -0,0 +1,374 @@
+// This file is part of the uutils procps package.
+//
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+use crate::ansi::{self, StyledLine};
+use ratatui::prelude::*;
+use ratatui::widgets::Clear;
+use std::time::Duration;
+use unicode_width::UnicodeWidthChar;
+
+const TAB_WIDTH: usize = 8;
+
+/// The title line: `Every 2.0s: command          hostname: Mon Oct 19 03:18:59 2026`
+pub(crate) struct Header<'a> {
+    pub interval: Duration,
+    pub command: &'a str,
+    pub hostname: &'a str,
+    pub time: &'a str,
+}
+
+impl Widget for Header<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let width = area.width as usize;
+        let left = format!(
+            "Every {:.1}s: {}",
+            self.interval.as_secs_f64(),
+            self.command
+        );
+        let right = format!("{}: {}", self.hostname, self.time);
+        let right_width = right.chars().map(|c| c.width().unwrap_or(0)).sum::<usize>();
+
+        // The hostname and time are dropped first, then the command is shortened.
+        let left_width = if right_width < width {
+            buf.set_string(
+                area.x + (width - right_width) as u16,
+                area.y,
+                &right,
+                Style::default(),
+            );
+            width - right_width - 1
+        } else {
+            width
+        };
+        buf.set_string(
+            area.x,
+            area.y,
+            truncate(&left, left_width),
+            Style::default(),
+        );
+    }
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    let text_width = text.chars().map(|c| c.width().unwrap_or(0)).sum::<usize>();
+    if text_width <= width {
+        return text.to_string();
+    }
+
+    let mut result = String::new();
+    let mut result_width = 0;
+    for c in text.chars() {
+        let char_width = c.width().unwrap_or(0);
+        if result_width + char_width + 3 > width {
+            break;
+        }
+        result.push(c);
+        result_width += char_width;
+    }
+    if width >= 3 {
+        result.push_str("...");
+    }
+    result
+}
+
+/// The full screen: the header with a blank line below it, then the output of
+/// the command wrapped at the terminal width and cut at its height.
+///
+/// With a reference output, the cells that differ from it are highlighted.
+pub(crate) struct WatchScreen<'a> {
+    pub header: Option<Header<'a>>,
+    pub output: &'a str,
+    /// Whether to interpret the colors and styles of the output
+    pub color: bool,
+    /// None to wrap the lines, or the number of columns they are scrolled by
+    pub scroll: Option<u16>,
+    pub reference: Option<&'a str>,
+    /// Shown in reverse video on the last line
+    pub message: Option<&'a str>,
+}
+
+impl Widget for WatchScreen<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut area = area;
+        if let Some(header) = self.header {
+            header.render(Rect { height: 1, ..area }, buf);
+            let offset = area.height.min(2);
+            area.y += offset;
+            area.height -= offset;
+        }
+        render_output(
+            &ansi::parse(self.output, self.color),
+            area,
+            buf,
+            self.scroll,
+        );
+
+        if let Some(reference) = self.reference {
+            let mut reference_buf = Buffer::empty(area);
+            render_output(
+                &ansi::parse(reference, self.color),
+                area,
+                &mut reference_buf,
+                self.scroll,
+            );
+            for position in area.positions() {
+                if buf[position].symbol() != reference_buf[position].symbol() {
+                    // Toggled so that changes stay visible in reversed output
+                    buf[position].modifier.toggle(Modifier::REVERSED);
+                }
+            }
+        }
+
+        if let Some(message) = self.message.filter(|_| !area.is_empty()) {
+            let y = area.bottom() - 1;
+            Clear.render(
+                Rect {
+                    y,
+                    height: 1,
+                    ..area
+                },
+                buf,
+            );
+            buf.set_stringn(
+                area.x,
+                y,
+                message,
+                area.width as usize,
+                Style::default().reversed(),
+            );
+        }
+    }
+}
+
+/// Put the lines in the area, wrapped or, with `scroll`, cut at its width
+/// after skipping that many columns.
+fn render_output(lines: &[StyledLine], area: Rect, buf: &mut Buffer, scroll: Option<u16>) {
+    if area.is_empty() {
+        return;
+    }
+
+    let mut y = area.y;
+    for line in lines {
+        // Column in the line, and where it starts on the screen. Lines can be
+        // wider than what a u16 counts.
+        let mut column = 0;
+        let mut start = scroll.map_or(0, usize::from);
+        let area_width = usize::from(area.width);
+        for &(c, style) in line {
+            if c == '\t' {
+                column = (column / TAB_WIDTH + 1) * TAB_WIDTH;
+                continue;
+            }
+            let width = match c.width() {
+                Some(width) if width > 0 => width,
+                _ => continue,
+            };
+
+            match scroll {
+                None if column - start + width > area_width => {
+                    start = column;
+                    y += 1;
+                    if y >= area.bottom() {
+                        return;
+                    }
+                }
+                Some(offset) if column + width > usize::from(offset) + area_width => break,
+                _ => {}
+            }
+            if column >= start {
+                // Within the area, so it fits
+                let x = area.x + (column - start) as u16;
+                buf.set_stringn(x, y, c.encode_utf8(&mut [0; 4]), width, style);
+            }
+            column += width;
+        }
+
+        y += 1;
+        if y >= area.bottom() {
+            return;
+        }
+    }
+}
+
+/// Width of the widest line, in columns, up to what a u16 counts.
+pub(crate) fn output_width(output: &str, color: bool) -> u16 {
+    let width = ansi::parse(output, color)
+        .iter()
+        .map(|line| {
+            line.iter().fold(0, |column, &(c, _)| match c {
+                '\t' => (column / TAB_WIDTH + 1) * TAB_WIDTH,
+                c => column + c.width().unwrap_or(0),
+            })
+        })
+        .max()
+        .unwrap_or(0);
+    u16::try_from(width).unwrap_or(u16::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(screen: WatchScreen, width: u16, height: u16) -> Vec<String> {
+        let area = Rect::new(0, 0, width, height);
+        let mut buf = Buffer::empty(area);
+        screen.render(area, &mut buf);
+        (0..height)
+            .map(|y| (0..width).map(|x| buf[(x, y)].symbol()).collect::<String>())
+            .collect()
+    }
+
+    #[test]
+    fn test_header() {
+        let header = Header {
+            interval: Duration::from_secs(2),
+            command: "date",
+            hostname: "host",
+            time: "Mon Oct 19 03:18:59 2026",
+        };
+        let screen = WatchScreen {
+            header: Some(header),
+            output: "Mon Oct 19\n",
+            color: false,
+            scroll: None,
+            reference: None,
+            message: None,
+        };
+        assert_eq!(
+            render(screen, 50, 4),
+            [
+                "Every 2.0s: date    host: Mon Oct 19 03:18:59 2026",
+                "",
+                "Mon Oct 19",
+                "",
+            ]
+            .map(|line| format!("{line:50}"))
+        );
+    }
+
+    #[test]
+    fn test_header_truncated() {
+        let header = Header {
+            interval: Duration::from_millis(500),
+            command: "a very long command line",
+            hostname: "host",
+            time: "12:00",
+        };
+        let screen = WatchScreen {
+            header: Some(header),
+            output: "",
+            color: false,
+            scroll: None,
+            reference: None,
+            message: None,
+        };
+        assert_eq!(render(screen, 30, 1), ["Every 0.5s: a v... host: 12:00"]);
+    }
+
+    #[test]
+    fn test_output_wrapped_and_truncated() {
+        let screen = WatchScreen {
+            header: None,
+            output: "abcdefgh\na\tb\x1b\n1\n2\n",
+            color: false,
+            scroll: None,
+            reference: None,
+            message: None,
+        };
+        assert_eq!(render(screen, 5, 4), ["abcde", "fgh  ", "a    ", "b    "]);
+    }
+
+    #[test]
+    fn test_differences() {
+        let area = Rect::new(0, 0, 6, 3);
+        let mut buf = Buffer::empty(area);
+        WatchScreen {
+            header: None,
+            output: "12:01\nsame\nlonger",
+            color: false,
+            scroll: None,
+            reference: Some("12:00\nsame\nlong\nextra"),
+            message: None,
+        }
+        .render(area, &mut buf);
+
+        let highlighted = area
+            .positions()
+            .filter(|&position| buf[position].modifier.contains(Modifier::REVERSED))
+            .map(|position| (position.x, position.y))
+            .collect::<Vec<_>>();
+        // The minute and the characters added to the last line
+        assert_eq!(highlighted, [(4, 0), (4, 2), (5, 2)]);
+    }
+
+    #[test]
+    fn test_colors_with_differences() {
+        let area = Rect::new(0, 0, 4, 1);
+        let mut buf = Buffer::empty(area);
+        WatchScreen {
+            header: None,
+            output: "\x1b[32mok\x1b[7m!!",
+            color: true,
+            scroll: None,
+            reference: Some("\x1b[31mko\x1b[7m!?"),
+            message: None,
+        }
+        .render(area, &mut buf);
+
+        assert_eq!(buf[(0, 0)].fg, Color::Green);
+        assert!(buf[(0, 0)].modifier.contains(Modifier::REVERSED));
+        assert!(buf[(2, 0)].modifier.contains(Modifier::REVERSED));
+        assert!(!buf[(3, 0)].modifier.contains(Modifier::REVERSED));
+        // The style alone doesn't make a difference
+        let mut buf = Buffer::empty(area);
+        WatchScreen {
+            header: None,
+            output: "\x1b[32mok",
+            color: true,
+            scroll: None,
+            reference: Some("\x1b[31mok"),
+            message: None,
+        }
+        .render(area, &mut buf);
+        assert!(!buf[(0, 0)].modifier.contains(Modifier::REVERSED));
+    }
+
+    #[test]
+    fn test_output_scrolled() {
+        let output = "0123456789\nab\t|\n\u{4e2d}\u{6587}";
+        let screen = |scroll| WatchScreen {
+            header: None,
+            output,
+            color: false,
+            scroll: Some(scroll),
+            reference: None,
+            message: None,
+        };
+        assert_eq!(
+            render(screen(0), 4, 3),
+            ["0123", "ab  ", "\u{4e2d} \u{6587} "]
+        );
+        // The half of a wide character is left blank
+        assert_eq!(render(screen(1), 4, 3), ["1234", "b   ", " \u{6587}  "]);
+        assert_eq!(render(screen(6), 4, 3), ["6789", "  | ", "    "]);
+        assert_eq!(output_width(output, false), 10);
+    }
+
+    #[test]
+    fn test_output_wider_than_u16() {
+        let output = format!("{}\nend", "x".repeat(70_000));
+        let screen = |scroll| WatchScreen {
+            header: None,
+            output: &output,
+            color: false,
+            scroll,
+            reference: None,
+            message: None,
+        };
+        assert_eq!(render(screen(None), 4, 2), ["xxxx", "xxxx"]);
+        assert_eq!(render(screen(Some(u16::MAX)), 4, 2), ["xxxx", "    "]);
+        assert_eq!(output_width(&output, false), u16::MAX);
+    }
+}
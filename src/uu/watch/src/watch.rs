@@ -4,13 +4,311 @@
 // file that was distributed with this source code.
 
 use clap::crate_version;
-use clap::{Arg, Command};
-use std::io::{Error, ErrorKind};
+use clap::{Arg, ArgAction, ArgGroup, ArgMatches, Command};
+use jiff::Zoned;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::DefaultTerminal;
+use regex::Regex;
+use std::collections::VecDeque;
+use std::io::{Error, ErrorKind, IsTerminal, Read, Write};
 use std::num::ParseIntError;
-use std::process::{Command as SystemCommand, Stdio};
-use std::thread::sleep;
-use std::time::Duration;
-use uucore::error::UResult;
+use std::path::PathBuf;
+use std::process::{Child, Command as SystemCommand, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::{self, sleep};
+use std::time::{Duration, Instant};
+use uucore::display::Quotable;
+use uucore::error::{set_exit_code, strip_errno, UResult, USimpleError};
+use uucore::show_warning;
+
+mod ansi;
+mod recorder;
+mod screen;
+
+use recorder::Recorder;
+use screen::{output_width, Header, WatchScreen};
+
+const BEEP: &[u8] = b"\x07";
+/// Exit code when --timeout expires, the one of timeout(1)
+const TIMEOUT_EXIT_CODE: i32 = 124;
+/// Exit code when the output matches --until
+const UNTIL_EXIT_CODE: i32 = 10;
+/// Exit code when the output no longer matches --while
+const WHILE_EXIT_CODE: i32 = 11;
+
+/// Why watch is done after a run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExitReason {
+    /// `--chgexit`
+    Changed,
+    /// `--equexit`
+    Unchanged,
+    Until,
+    While,
+}
+
+impl ExitReason {
+    fn exit_code(self) -> i32 {
+        match self {
+            Self::Changed | Self::Unchanged => 0,
+            Self::Until => UNTIL_EXIT_CODE,
+            Self::While => WHILE_EXIT_CODE,
+        }
+    }
+}
+
+/// The command being run, shared with the thread running it so that it can be
+/// killed when --timeout expires.
+type Running = Arc<Mutex<Option<Child>>>;
+
+/// What changes are highlighted with `--differences`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Differences {
+    /// Changes since the previous run
+    Previous,
+    /// Changes since the first run
+    Permanent,
+}
+
+struct Settings {
+    /// The command line, as shown in the header
+    command: String,
+    args: Vec<String>,
+    exec: bool,
+    interval: Duration,
+    precise: bool,
+    no_title: bool,
+    no_rerun: bool,
+    no_wrap: bool,
+    color: bool,
+    differences: Option<Differences>,
+    /// Number of runs kept to be browsed
+    history: usize,
+    output_dir: Option<PathBuf>,
+    log: Option<PathBuf>,
+    /// Only record the outputs, without showing them
+    no_screen: bool,
+    beep: bool,
+    errexit: bool,
+    chgexit: bool,
+    equexit: Option<u32>,
+    until: Option<Regex>,
+    while_matching: Option<Regex>,
+    timeout: Option<Duration>,
+}
+
+impl Settings {
+    fn new(matches: &ArgMatches) -> UResult<Self> {
+        let args = matches
+            .get_many::<String>("command")
+            .expect("required argument")
+            .cloned()
+            .collect::<Vec<_>>();
+        let interval = match matches.get_one::<String>("interval") {
+            None => Duration::from_secs(2),
+            Some(input) => match parse_interval(input) {
+                Ok(interval) => interval,
+                Err(_) => {
+                    return Err(Box::from(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("watch: failed to parse argument: '{input}': Invalid argument"),
+                    )));
+                }
+            },
+        };
+
+        let timeout = match matches.get_one::<String>("timeout") {
+            None => None,
+            Some(input) => Some(parse_interval(input).map_err(|_| {
+                USimpleError::new(
+                    1,
+                    format!(
+                        "failed to parse argument: {}: Invalid argument",
+                        input.quote()
+                    ),
+                )
+            })?),
+        };
+
+        Ok(Self {
+            command: args.join(" "),
+            args,
+            exec: matches.get_flag("exec"),
+            interval,
+            precise: matches.get_flag("precise"),
+            no_title: matches.get_flag("no-title"),
+            no_rerun: matches.get_flag("no-rerun"),
+            no_wrap: matches.get_flag("no-wrap"),
+            color: matches.get_flag("color"),
+            differences: matches.contains_id("differences").then(|| {
+                match matches.get_one::<String>("differences") {
+                    Some(_) => Differences::Permanent,
+                    None => Differences::Previous,
+                }
+            }),
+            history: matches.get_one::<u32>("history").copied().unwrap_or(0) as usize,
+            output_dir: matches.get_one("output-dir").cloned(),
+            log: matches.get_one("log").cloned(),
+            no_screen: matches.get_flag("no-screen"),
+            beep: matches.get_flag("beep"),
+            errexit: matches.get_flag("errexit"),
+            chgexit: matches.get_flag("chgexit"),
+            equexit: matches.get_one("equexit").copied(),
+            until: matches.get_one("until").cloned(),
+            while_matching: matches.get_one("while").cloned(),
+            timeout,
+        })
+    }
+
+    /// The command to run, `sh -c` with the command line unless `--exec` is given.
+    fn system_command(&self) -> SystemCommand {
+        let mut command = if self.exec {
+            let mut command = SystemCommand::new(&self.args[0]);
+            command.args(&self.args[1..]);
+            command
+        } else {
+            #[cfg(windows)]
+            let mut command =
+                SystemCommand::new(std::env::var_os("COMSPEC").unwrap_or_else(|| "cmd.exe".into()));
+            #[cfg(not(windows))]
+            let mut command = SystemCommand::new("sh");
+
+            #[cfg(windows)]
+            command.arg("/c");
+            #[cfg(not(windows))]
+            command.arg("-c");
+
+            command.arg(&self.command);
+            command
+        };
+
+        // So that it is killed along with what it started when --timeout expires
+        #[cfg(unix)]
+        if self.timeout.is_some() {
+            use std::os::unix::process::CommandExt;
+            command.process_group(0);
+        }
+        command
+    }
+}
+
+/// Output of one run of the watched command, stdout and stderr interleaved.
+struct Run {
+    /// Position among all the runs, counted from 1
+    number: u64,
+    output: String,
+    status: ExitStatus,
+    /// When the run started
+    time: Zoned,
+}
+
+impl Run {
+    /// The exit code of watch when it stops because of this run failing.
+    fn exit_code(&self) -> i32 {
+        // Like procps-ng, 8 stands for the command exiting in error
+        self.status.code().unwrap_or(8)
+    }
+}
+
+/// The runs the screen is built from, the last ones up to `--history`.
+struct Runs {
+    first: Option<String>,
+    history: VecDeque<Run>,
+    capacity: usize,
+    /// Number of runs in a row with the same output as the one before
+    unchanged: u32,
+}
+
+impl Runs {
+    fn new(capacity: usize) -> Self {
+        Self {
+            first: None,
+            history: VecDeque::new(),
+            // The previous run is needed for the differences
+            capacity: capacity.max(2),
+            unchanged: 0,
+        }
+    }
+
+    fn push(&mut self, mut run: Run) {
+        run.number = self.last().map_or(1, |last| last.number + 1);
+        if self.first.is_none() {
+            self.first = Some(run.output.clone());
+        }
+        if self.history.len() == self.capacity {
+            self.history.pop_front();
+        }
+        self.history.push_back(run);
+
+        if self.changed() {
+            self.unchanged = 0;
+        } else if self.history.len() > 1 {
+            self.unchanged += 1;
+        }
+    }
+
+    fn last(&self) -> Option<&Run> {
+        self.history.back()
+    }
+
+    /// The run numbered `number`, if it is still kept.
+    fn get(&self, number: u64) -> Option<&Run> {
+        let oldest = self.history.front()?.number;
+        self.history
+            .get(usize::try_from(number.checked_sub(oldest)?).ok()?)
+    }
+
+    fn changed(&self) -> bool {
+        match self.last() {
+            Some(last) => self
+                .get(last.number - 1)
+                .is_some_and(|previous| previous.output != last.output),
+            None => false,
+        }
+    }
+
+    /// Whether the output of the last run differs from the one before, if any.
+    fn is_new_output(&self) -> bool {
+        self.changed() || self.history.len() == 1
+    }
+
+    /// Whether watch is done according to `--chgexit`, `--equexit`, `--until`
+    /// and `--while`, and why.
+    fn should_exit(&self, settings: &Settings) -> Option<ExitReason> {
+        let output = self.last().map_or("", |run| run.output.as_str());
+        if settings.chgexit && self.changed() {
+            Some(ExitReason::Changed)
+        } else if settings
+            .equexit
+            .is_some_and(|cycles| self.unchanged >= cycles)
+        {
+            Some(ExitReason::Unchanged)
+        } else if settings
+            .until
+            .as_ref()
+            .is_some_and(|re| re.is_match(output))
+        {
+            Some(ExitReason::Until)
+        } else if settings
+            .while_matching
+            .as_ref()
+            .is_some_and(|re| !re.is_match(output))
+        {
+            Some(ExitReason::While)
+        } else {
+            None
+        }
+    }
+
+    /// The output `run` is compared to.
+    fn reference(&self, run: &Run, differences: Option<Differences>) -> Option<&str> {
+        match differences? {
+            Differences::Previous => self.get(run.number - 1).map(|run| run.output.as_str()),
+            Differences::Permanent => self.first.as_deref(),
+        }
+    }
+}
 
 fn parse_interval(input: &str) -> Result<Duration, ParseIntError> {
     // Find index where to split string into seconds and nanos
@@ -57,49 +355,545 @@ fn parse_interval(input: &str) -> Result<Duration, ParseIntError> {
 #[uucore::main]
 pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     let matches = uu_app().try_get_matches_from(args)?;
+    let settings = Settings::new(&matches)?;
+    let recorder = if settings.output_dir.is_some() || settings.log.is_some() {
+        Some(Recorder::new(
+            settings.output_dir.as_deref(),
+            settings.log.as_deref(),
+        )?)
+    } else {
+        None
+    };
+
+    // Without a terminal there is no screen to redraw, so the output of every
+    // run is passed through as it is.
+    if settings.no_screen || !std::io::stdout().is_terminal() {
+        return run_plain(&settings, recorder);
+    }
+
+    #[cfg(unix)]
+    handle_termination_signals()?;
+
+    let mut terminal = ratatui::init();
+    let result = run_fullscreen(&mut terminal, &settings, recorder);
+    ratatui::restore();
+    result
+}
 
-    let command_to_watch = matches
-        .get_one::<String>("command")
-        .expect("required argument");
-    let interval = match matches.get_one::<String>("interval") {
-        None => Duration::from_secs(2),
-        Some(input) => match parse_interval(input) {
-            Ok(interval) => interval,
-            Err(_) => {
-                return Err(Box::from(Error::new(
-                    ErrorKind::InvalidInput,
-                    format!("watch: failed to parse argument: '{input}': Invalid argument"),
-                )));
+/// When the run following one scheduled at `scheduled` and finished at
+/// `finished` is due, along with the number of runs skipped because the
+/// command took longer than the interval.
+///
+/// In precise mode the runs keep a fixed cadence, otherwise the interval is
+/// waited after each run.
+fn next_run(
+    scheduled: Instant,
+    finished: Instant,
+    interval: Duration,
+    precise: bool,
+) -> (Instant, u32) {
+    if !precise {
+        return (finished + interval, 0);
+    }
+
+    let mut next = scheduled + interval;
+    let mut skipped = 0;
+    while next < finished {
+        next += interval;
+        skipped += 1;
+    }
+    (next, skipped)
+}
+
+fn skipped_message(skipped: u32, interval: Duration) -> String {
+    format!(
+        "skipped {skipped} update(s), the command took longer than {:.1}s",
+        interval.as_secs_f64()
+    )
+}
+
+/// Write the last run with the recorder, if it is a new output.
+fn record(recorder: Option<&mut Recorder>, runs: &Runs) -> UResult<()> {
+    match (recorder, runs.last()) {
+        (Some(recorder), Some(run)) if runs.is_new_output() => {
+            recorder.record(run.number, &run.time, run.status, &run.output)
+        }
+        _ => Ok(()),
+    }
+}
+
+fn run_plain(settings: &Settings, mut recorder: Option<Recorder>) -> UResult<()> {
+    let mut runs = Runs::new(2);
+    let mut stdout = std::io::stdout();
+    let mut scheduled = Instant::now();
+    let deadline = settings.timeout.map(|timeout| scheduled + timeout);
+    let running = Running::default();
+    loop {
+        let run = match deadline {
+            // In another thread, as the command may well be what takes too long
+            Some(deadline) => {
+                let (sender, receiver) = mpsc::channel();
+                let command = settings.system_command();
+                let shared = running.clone();
+                thread::spawn(move || sender.send(execute(command, None, &shared)));
+                match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                    Ok(run) => run?,
+                    Err(_) => {
+                        kill(&running);
+                        set_exit_code(TIMEOUT_EXIT_CODE);
+                        return Ok(());
+                    }
+                }
             }
-        },
-    };
+            None => execute(settings.system_command(), None, &running)?,
+        };
+        let failed = !run.status.success();
+        let exit_code = run.exit_code();
+        if !settings.no_screen {
+            stdout.write_all(run.output.as_bytes())?;
+        }
+        runs.push(run);
+        record(recorder.as_mut(), &runs)?;
+
+        if failed && settings.beep {
+            stdout.write_all(BEEP)?;
+        }
+        stdout.flush()?;
+        if failed && settings.errexit {
+            set_exit_code(exit_code);
+            return Ok(());
+        }
+        if let Some(reason) = runs.should_exit(settings) {
+            set_exit_code(reason.exit_code());
+            return Ok(());
+        }
+
+        let (next, skipped) = next_run(
+            scheduled,
+            Instant::now(),
+            settings.interval,
+            settings.precise,
+        );
+        if skipped > 0 {
+            show_warning!("{}", skipped_message(skipped, settings.interval));
+        }
+        if let Some(deadline) = deadline.filter(|&deadline| deadline < next) {
+            sleep(deadline.saturating_duration_since(Instant::now()));
+            set_exit_code(TIMEOUT_EXIT_CODE);
+            return Ok(());
+        }
+        sleep(next.saturating_duration_since(Instant::now()));
+        scheduled = next;
+    }
+}
+
+/// Run the command once, the exit codes of the failures are those of procps-ng.
+///
+/// The command is in `running` while it runs.
+fn execute(
+    mut command: SystemCommand,
+    size: Option<(u16, u16)>,
+    running: &Running,
+) -> UResult<Run> {
+    let time = Zoned::now();
+
+    let (mut reader, stdout, stderr) = std::io::pipe()
+        .and_then(|(reader, writer)| Ok((reader, writer.try_clone()?, writer)))
+        .map_err(|e| USimpleError::new(7, format!("unable to create IPC pipes: {e}")))?;
+    if let Some((columns, lines)) = size {
+        command
+            .env("COLUMNS", columns.to_string())
+            .env("LINES", lines.to_string());
+    }
+    command.stdin(Stdio::null()).stdout(stdout).stderr(stderr);
+    let child = command.spawn().map_err(|e| match e.kind() {
+        ErrorKind::NotFound | ErrorKind::PermissionDenied => USimpleError::new(
+            4,
+            format!(
+                "unable to execute {}: {}",
+                command.get_program().quote(),
+                strip_errno(&e)
+            ),
+        ),
+        _ => USimpleError::new(2, format!("unable to fork process: {e}")),
+    })?;
+    *running.lock().unwrap() = Some(child);
+    // The command keeps the write end open, reading would never finish.
+    drop(command);
+
+    let mut output = Vec::new();
+    reader.read_to_end(&mut output)?;
+    let status = running
+        .lock()
+        .unwrap()
+        .take()
+        .ok_or_else(|| Error::other("the command was killed"))
+        .and_then(|mut child| child.wait())
+        .map_err(|e| USimpleError::new(8, format!("waitpid: {e}")))?;
+
+    Ok(Run {
+        number: 0,
+        output: String::from_utf8_lossy(&output).into_owned(),
+        status,
+        time,
+    })
+}
+
+/// Kill the command if it is still running.
+fn kill(running: &Running) {
+    if let Some(mut child) = running.lock().unwrap().take() {
+        // It may have exited already, it is reaped either way
+        #[cfg(unix)]
+        {
+            use nix::sys::signal::{killpg, Signal};
+            use nix::unistd::Pid;
+            // Its process group, with what the shell started
+            let _ = killpg(Pid::from_raw(child.id() as i32), Signal::SIGKILL);
+        }
+        #[cfg(not(unix))]
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
+
+const PAUSED_MESSAGE: &str = "paused, press p to resume";
+const INTERVAL_STEP: Duration = Duration::from_millis(500);
+/// Columns scrolled by with the arrow keys, with --no-wrap
+const SCROLL_STEP: u16 = 8;
+const MIN_INTERVAL: Duration = Duration::from_millis(100);
+
+/// State of the full screen mode.
+struct State<'a> {
+    settings: &'a Settings,
+    hostname: String,
+    runs: Runs,
+    /// Shown on the last line
+    message: Option<String>,
+    /// Can be changed with '+' and '-'
+    interval: Duration,
+    /// Toggled with 'd'
+    differences: Option<Differences>,
+    /// Columns the output is scrolled by, with --no-wrap
+    scroll: u16,
+    /// Number of the run browsed in the history, None to follow the last one
+    shown: Option<u64>,
+    /// Number of the run the shown one is compared to, marked with 'm'
+    marked: Option<u64>,
+    /// No run is started while paused, unless forced with space
+    paused: bool,
+    /// When the next run is due, None while the command is running
+    due: Option<Instant>,
+    /// When the last run was due
+    scheduled: Instant,
+    /// Whether to start a run right away
+    forced: bool,
+    /// Exit code to use once a key is pressed, after an error with --errexit
+    frozen: Option<i32>,
+}
+
+impl<'a> State<'a> {
+    fn new(settings: &'a Settings) -> Self {
+        Self {
+            settings,
+            hostname: hostname(),
+            runs: Runs::new(settings.history),
+            message: None,
+            interval: settings.interval,
+            differences: settings.differences,
+            scroll: 0,
+            shown: None,
+            marked: None,
+            paused: false,
+            due: Some(Instant::now()),
+            scheduled: Instant::now(),
+            forced: false,
+            frozen: None,
+        }
+    }
+
+    /// Whether it is time to start a run.
+    fn should_run(&self) -> bool {
+        self.due
+            .is_some_and(|due| self.forced || (!self.paused && due <= Instant::now()))
+    }
+
+    /// How long to wait for a key press, at most until the next run or the
+    /// deadline. A run due while paused doesn't count, it only starts once
+    /// forced.
+    fn poll_timeout(&self, deadline: Option<Instant>) -> Duration {
+        self.due
+            .filter(|_| !self.paused || self.forced)
+            .into_iter()
+            .chain(deadline)
+            .min()
+            .map(|next| next.saturating_duration_since(Instant::now()))
+            .unwrap_or(Duration::MAX)
+            .min(Duration::from_millis(50))
+    }
+
+    /// Take in a run, returns whether watch is done.
+    fn push(&mut self, run: Run) -> bool {
+        let settings = self.settings;
+        let failed = !run.status.success();
+        let exit_code = run.exit_code();
+        self.runs.push(run);
+        // What got out of the history is replaced by the oldest run kept
+        let oldest = self.runs.history.front().map_or(0, |run| run.number);
+        self.shown = self.shown.map(|shown| shown.max(oldest));
+        self.marked = self.marked.filter(|&marked| marked >= oldest);
+
+        if failed && settings.errexit {
+            self.message = Some("command exit with a non-zero status, press a key to exit".into());
+            self.frozen = Some(exit_code);
+        } else if let Some(reason) = self.runs.should_exit(settings) {
+            set_exit_code(reason.exit_code());
+            return true;
+        } else {
+            let (next, skipped) = next_run(
+                self.scheduled,
+                Instant::now(),
+                self.interval,
+                settings.precise,
+            );
+            self.message = (skipped > 0).then(|| skipped_message(skipped, self.interval));
+            self.due = Some(next);
+        }
+        false
+    }
+
+    /// Handle a key press, returns whether watch is done.
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return true;
+        }
+        if let Some(exit_code) = self.frozen {
+            set_exit_code(exit_code);
+            return true;
+        }
+
+        match key.code {
+            KeyCode::Char('q') => return true,
+            KeyCode::Char(' ') => self.forced = true,
+            KeyCode::Char('p') => self.paused = !self.paused,
+            KeyCode::Char('+') => self.interval += INTERVAL_STEP,
+            KeyCode::Char('-') => {
+                self.interval = self
+                    .interval
+                    .saturating_sub(INTERVAL_STEP)
+                    .max(MIN_INTERVAL);
+            }
+            KeyCode::Left | KeyCode::Right | KeyCode::Home if self.settings.no_wrap => {
+                let last_column = self
+                    .shown_run()
+                    .map(|run| output_width(&run.output, self.settings.color))
+                    .unwrap_or(0)
+                    .saturating_sub(1);
+                self.scroll = match key.code {
+                    KeyCode::Left => self.scroll.saturating_sub(SCROLL_STEP),
+                    KeyCode::Right => (self.scroll + SCROLL_STEP).min(last_column),
+                    _ => 0,
+                };
+            }
+            KeyCode::Char('[') => {
+                if let Some(run) = self.shown_run() {
+                    let oldest = self.runs.history.front().map_or(0, |run| run.number);
+                    self.shown = Some(run.number.saturating_sub(1).max(oldest));
+                }
+            }
+            KeyCode::Char(']') => {
+                let last = self.runs.last().map_or(0, |run| run.number);
+                self.shown = self
+                    .shown
+                    .map(|shown| shown + 1)
+                    .filter(|&shown| shown < last);
+            }
+            KeyCode::End => self.shown = None,
+            KeyCode::Char('m') => {
+                let shown = self.shown_run().map(|run| run.number);
+                self.marked = if self.marked == shown { None } else { shown };
+            }
+            KeyCode::Char('d') => {
+                self.differences = match self.differences {
+                    Some(_) => None,
+                    None => Some(self.settings.differences.unwrap_or(Differences::Previous)),
+                };
+            }
+            _ => {}
+        }
+        false
+    }
+
+    /// The run on the screen.
+    fn shown_run(&self) -> Option<&Run> {
+        match self.shown {
+            Some(number) => self.runs.get(number),
+            None => self.runs.last(),
+        }
+    }
+
+    /// The line describing the shown run when browsing the history.
+    fn history_message(&self, run: &Run) -> Option<String> {
+        if self.shown.is_none() && self.marked.is_none() {
+            return None;
+        }
+
+        let mut message = format!(
+            "run {}/{}, {}",
+            run.number,
+            self.runs.last().map_or(0, |run| run.number),
+            run.status
+        );
+        if let Some(marked) = self.marked {
+            message += &format!(", compared with run {marked}");
+        }
+        if self.shown.is_some() {
+            message += ", End to follow";
+        }
+        Some(message)
+    }
+
+    fn draw(&self, terminal: &mut DefaultTerminal) -> std::io::Result<()> {
+        let Some(run) = self.shown_run() else {
+            return Ok(());
+        };
+        let history_message = self.history_message(run);
+        let message = if history_message.is_some() {
+            history_message.as_deref()
+        } else if self.paused {
+            Some(PAUSED_MESSAGE)
+        } else {
+            self.message.as_deref()
+        };
+        let reference = match self.marked.and_then(|marked| self.runs.get(marked)) {
+            Some(marked) => Some(marked.output.as_str()),
+            None => self.runs.reference(run, self.differences),
+        };
+        let time = run.time.strftime("%a %b %e %H:%M:%S %Y").to_string();
+        terminal.draw(|frame| {
+            let header = (!self.settings.no_title).then(|| Header {
+                interval: self.interval,
+                command: &self.settings.command,
+                hostname: &self.hostname,
+                time: &time,
+            });
+            frame.render_widget(
+                WatchScreen {
+                    header,
+                    output: &run.output,
+                    color: self.settings.color,
+                    scroll: self.settings.no_wrap.then_some(self.scroll),
+                    reference,
+                    message,
+                },
+                frame.area(),
+            );
+        })?;
+        Ok(())
+    }
+}
+
+fn run_fullscreen(
+    terminal: &mut DefaultTerminal,
+    settings: &Settings,
+    mut recorder: Option<Recorder>,
+) -> UResult<()> {
+    let (sender, receiver) = mpsc::channel();
+    let mut state = State::new(settings);
+    let deadline = settings.timeout.map(|timeout| Instant::now() + timeout);
+    let running = Running::default();
 
     loop {
-        #[cfg(windows)]
-        let mut command =
-            SystemCommand::new(std::env::var_os("COMSPEC").unwrap_or_else(|| "cmd.exe".into()));
-        #[cfg(not(windows))]
-        let mut command = SystemCommand::new("sh");
-
-        #[cfg(windows)]
-        command.arg("/c");
-        #[cfg(not(windows))]
-        command.arg("-c");
-
-        let output = command
-            .arg(command_to_watch)
-            .stdout(Stdio::inherit())
-            .stderr(Stdio::inherit())
-            .output()?;
-
-        if !output.status.success() {
-            eprintln!("watch: command failed: {:?}", output.status);
+        if state.should_run() {
+            state.scheduled = state.due.take().unwrap_or_else(Instant::now);
+            state.forced = false;
+            let sender = sender.clone();
+            let command = settings.system_command();
+            let size = terminal.size()?;
+            // The command runs in the background so that the screen stays responsive.
+            let running = running.clone();
+            thread::spawn(move || {
+                sender.send(execute(command, Some((size.width, size.height)), &running))
+            });
+        }
+
+        if let Ok(run) = receiver.try_recv() {
+            let run = run?;
+            let failed = !run.status.success();
+            let done = state.push(run);
+            record(recorder.as_mut(), &state.runs)?;
+            if done {
+                break;
+            }
+            state.draw(terminal)?;
+            if failed && settings.beep {
+                terminal.backend_mut().write_all(BEEP)?;
+                terminal.backend_mut().flush()?;
+            }
+        }
+
+        if TERMINATED.load(Ordering::Relaxed) {
             break;
         }
+        if deadline.is_some_and(|deadline| deadline <= Instant::now()) {
+            kill(&running);
+            set_exit_code(TIMEOUT_EXIT_CODE);
+            break;
+        }
+
+        if !event::poll(state.poll_timeout(deadline))? {
+            continue;
+        }
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => {
+                if state.handle_key(key) {
+                    break;
+                }
+                state.draw(terminal)?;
+            }
+            Event::Resize(..) => {
+                // The output of the command may depend on the size of the terminal
+                if !settings.no_rerun && state.frozen.is_none() {
+                    state.forced = true;
+                }
+                state.draw(terminal)?;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn hostname() -> String {
+    rustix::system::uname()
+        .nodename()
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[cfg(not(unix))]
+fn hostname() -> String {
+    std::env::var("COMPUTERNAME").unwrap_or_default()
+}
 
-        sleep(interval);
+static TERMINATED: AtomicBool = AtomicBool::new(false);
+
+/// Raw mode turns Ctrl-C into a key press, but SIGTERM and SIGHUP still have to
+/// leave the terminal usable.
+#[cfg(unix)]
+fn handle_termination_signals() -> UResult<()> {
+    use nix::sys::signal::{signal, SigHandler, Signal};
+
+    extern "C" fn terminate(_: nix::libc::c_int) {
+        TERMINATED.store(true, Ordering::Relaxed);
     }
 
+    for sig in [Signal::SIGTERM, Signal::SIGHUP] {
+        // SAFETY: the handler only stores to an atomic.
+        unsafe { signal(sig, SigHandler::Handler(terminate)) }
+            .map_err(|e| Error::from_raw_os_error(e as i32))?;
+    }
     Ok(())
 }
 
@@ -112,6 +906,8 @@ pub fn uu_app() -> Command {
         .arg(
             Arg::new("command")
                 .required(true)
+                .num_args(1..)
+                .trailing_var_arg(true)
                 .help("Command to be executed"),
         )
         .arg(
@@ -127,18 +923,23 @@ pub fn uu_app() -> Command {
             Arg::new("beep")
                 .short('b')
                 .long("beep")
+                .action(ArgAction::SetTrue)
                 .help("Beep if command has a non-zero exit"),
         )
         .arg(
             Arg::new("color")
                 .short('c')
                 .long("color")
+                .action(ArgAction::SetTrue)
+                .overrides_with("no-color")
                 .help("Interpret ANSI color and style sequences"),
         )
         .arg(
             Arg::new("no-color")
                 .short('C')
                 .long("no-color")
+                .action(ArgAction::SetTrue)
+                .overrides_with("color")
                 .help("Do not interpret ANSI color and style sequences"),
         )
         .arg(
@@ -146,18 +947,57 @@ pub fn uu_app() -> Command {
                 .short('d')
                 .long("differences")
                 .value_name("permanent")
+                .num_args(0..=1)
+                .require_equals(true)
+                .value_parser(["permanent"])
                 .help("Highlight changes between updates"),
         )
+        .arg(
+            Arg::new("history")
+                .long("history")
+                .value_name("RUNS")
+                .value_parser(clap::value_parser!(u32).range(1..))
+                .default_value("100")
+                .help("Keep the output of the last RUNS runs to be browsed"),
+        )
+        .arg(
+            Arg::new("output-dir")
+                .long("output-dir")
+                .value_name("DIR")
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("Save each output that differs from the previous one to a file in DIR"),
+        )
+        .arg(
+            Arg::new("log")
+                .long("log")
+                .value_name("FILE")
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("Append each output that differs from the previous one to FILE"),
+        )
+        .arg(
+            Arg::new("no-screen")
+                .long("no-screen")
+                .action(ArgAction::SetTrue)
+                .requires("record")
+                .help("Only save the outputs, without showing them"),
+        )
+        .group(
+            ArgGroup::new("record")
+                .args(["output-dir", "log"])
+                .multiple(true),
+        )
         .arg(
             Arg::new("errexit")
                 .short('e')
                 .long("errexit")
+                .action(ArgAction::SetTrue)
                 .help("Exit if command has a non-zero exit"),
         )
         .arg(
             Arg::new("chgexit")
                 .short('g')
                 .long("chgexit")
+                .action(ArgAction::SetTrue)
                 .help("Exit when output from command changes"),
         )
         .arg(
@@ -165,36 +1005,62 @@ pub fn uu_app() -> Command {
                 .short('q')
                 .long("equexit")
                 .value_name("CYCLES")
+                .value_parser(clap::value_parser!(u32).range(1..))
                 .help("Exit when output from command does not change"),
         )
+        .arg(
+            Arg::new("until")
+                .long("until")
+                .value_name("REGEX")
+                .value_parser(Regex::new)
+                .help("Exit with status 10 when the output matches REGEX"),
+        )
+        .arg(
+            Arg::new("while")
+                .long("while")
+                .value_name("REGEX")
+                .value_parser(Regex::new)
+                .help("Exit with status 11 when the output does not match REGEX anymore"),
+        )
+        .arg(
+            Arg::new("timeout")
+                .long("timeout")
+                .value_name("SECONDS")
+                .help("Exit with status 124 after SECONDS"),
+        )
         .arg(
             Arg::new("precise")
                 .short('p')
                 .long("precise")
+                .action(ArgAction::SetTrue)
                 .help("Attempt to run command in precise intervals"),
         )
         .arg(
             Arg::new("no-rerun")
                 .short('r')
                 .long("no-rerun")
+                .action(ArgAction::SetTrue)
                 .help("Do not rerun program on window resize"),
         )
         .arg(
             Arg::new("no-title")
                 .short('t')
                 .long("no-title")
+                .action(ArgAction::SetTrue)
                 .help("Turn off header"),
         )
         .arg(
             Arg::new("no-wrap")
                 .short('w')
                 .long("no-wrap")
+                .action(ArgAction::SetTrue)
                 .help("Turn off line wrapping"),
         )
         .arg(
             Arg::new("exec")
                 .short('x')
                 .long("exec")
+                .action(ArgAction::SetTrue)
                 .help("Pass command to exec instead of 'sh -c'"),
         )
 }
@@ -265,3 +1131,184 @@ mod parse_interval_tests {
         assert_eq!(Ok(Duration::from_millis(100)), interval);
     }
 }
+
+#[cfg(test)]
+mod next_run_tests {
+    use super::*;
+
+    #[test]
+    fn test_interval_after_run() {
+        let scheduled = Instant::now();
+        let finished = scheduled + Duration::from_millis(700);
+        let interval = Duration::from_secs(1);
+        assert_eq!(
+            next_run(scheduled, finished, interval, false),
+            (finished + interval, 0)
+        );
+    }
+
+    #[test]
+    fn test_precise() {
+        let scheduled = Instant::now();
+        let finished = scheduled + Duration::from_millis(700);
+        let interval = Duration::from_secs(1);
+        assert_eq!(
+            next_run(scheduled, finished, interval, true),
+            (scheduled + interval, 0)
+        );
+    }
+
+    #[test]
+    fn test_precise_skips_missed_runs() {
+        let scheduled = Instant::now();
+        let finished = scheduled + Duration::from_millis(2500);
+        let interval = Duration::from_secs(1);
+        assert_eq!(
+            next_run(scheduled, finished, interval, true),
+            (scheduled + Duration::from_secs(3), 2)
+        );
+    }
+}
+
+#[cfg(test)]
+mod key_tests {
+    use super::*;
+
+    fn settings(args: &[&str]) -> Settings {
+        let matches = uu_app().get_matches_from([&["watch"], args].concat());
+        Settings::new(&matches).unwrap()
+    }
+
+    fn press(state: &mut State, c: char) -> bool {
+        state.handle_key(KeyEvent::from(KeyCode::Char(c)))
+    }
+
+    #[test]
+    fn test_interval() {
+        let settings = settings(&["-n", "1", "true"]);
+        let mut state = State::new(&settings);
+        press(&mut state, '+');
+        assert_eq!(state.interval, Duration::from_millis(1500));
+        for _ in 0..4 {
+            press(&mut state, '-');
+        }
+        assert_eq!(state.interval, MIN_INTERVAL);
+    }
+
+    #[test]
+    fn test_toggle_differences() {
+        let settings = settings(&["--differences=permanent", "true"]);
+        let mut state = State::new(&settings);
+        press(&mut state, 'd');
+        assert_eq!(state.differences, None);
+        press(&mut state, 'd');
+        assert_eq!(state.differences, Some(Differences::Permanent));
+    }
+
+    #[test]
+    fn test_pause_and_force() {
+        let settings = settings(&["true"]);
+        let mut state = State::new(&settings);
+        assert!(state.should_run());
+        press(&mut state, 'p');
+        assert!(!state.should_run());
+        press(&mut state, ' ');
+        assert!(state.should_run());
+        assert!(press(&mut state, 'q'));
+    }
+
+    #[test]
+    fn test_poll_timeout_while_paused() {
+        let settings = settings(&["true"]);
+        let mut state = State::new(&settings);
+        state.due = Some(Instant::now() - Duration::from_secs(1));
+        assert_eq!(state.poll_timeout(None), Duration::ZERO);
+
+        // The overdue run doesn't make the wait shorter
+        press(&mut state, 'p');
+        assert_eq!(state.poll_timeout(None), Duration::from_millis(50));
+        let deadline = Instant::now() - Duration::from_secs(1);
+        assert_eq!(state.poll_timeout(Some(deadline)), Duration::ZERO);
+
+        press(&mut state, ' ');
+        assert_eq!(state.poll_timeout(None), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_scroll() {
+        let settings = settings(&["--no-wrap", "true"]);
+        let mut state = State::new(&settings);
+        state.runs.push(Run {
+            number: 0,
+            output: "a line of twenty cols\n".into(),
+            status: ExitStatus::default(),
+            time: Zoned::now(),
+        });
+        state.handle_key(KeyEvent::from(KeyCode::Right));
+        assert_eq!(state.scroll, SCROLL_STEP);
+        for _ in 0..3 {
+            state.handle_key(KeyEvent::from(KeyCode::Right));
+        }
+        assert_eq!(state.scroll, 20);
+        state.handle_key(KeyEvent::from(KeyCode::Left));
+        assert_eq!(state.scroll, 12);
+        state.handle_key(KeyEvent::from(KeyCode::Home));
+        assert_eq!(state.scroll, 0);
+    }
+}
+
+#[cfg(test)]
+mod history_tests {
+    use super::*;
+
+    fn run(output: &str) -> Run {
+        Run {
+            number: 0,
+            output: output.into(),
+            status: ExitStatus::default(),
+            time: Zoned::now(),
+        }
+    }
+
+    #[test]
+    fn test_history_capacity() {
+        let mut runs = Runs::new(3);
+        for output in ["a", "b", "c", "d"] {
+            runs.push(run(output));
+        }
+        assert!(runs.get(1).is_none());
+        assert_eq!(runs.get(2).unwrap().output, "b");
+        assert_eq!(runs.last().unwrap().number, 4);
+        assert_eq!(runs.first.as_deref(), Some("a"));
+    }
+
+    #[test]
+    fn test_browse_and_compare() {
+        let matches = uu_app().get_matches_from(["watch", "--history", "3", "true"]);
+        let settings = Settings::new(&matches).unwrap();
+        let mut state = State::new(&settings);
+        for output in ["a", "b", "c"] {
+            state.push(run(output));
+        }
+        let press = |state: &mut State, c| state.handle_key(KeyEvent::from(KeyCode::Char(c)));
+
+        press(&mut state, '[');
+        press(&mut state, '[');
+        press(&mut state, '[');
+        assert_eq!(state.shown_run().unwrap().output, "a");
+        press(&mut state, 'm');
+        press(&mut state, ']');
+        assert_eq!(state.shown_run().unwrap().output, "b");
+        assert_eq!(state.marked, Some(1));
+
+        // The marked run goes away with the history, the shown one is kept in it
+        state.push(run("d"));
+        assert_eq!(state.marked, None);
+        state.push(run("e"));
+        assert_eq!(state.shown_run().unwrap().output, "c");
+        press(&mut state, ']');
+        press(&mut state, ']');
+        assert_eq!(state.shown, None);
+        assert_eq!(state.shown_run().unwrap().output, "e");
+    }
+}
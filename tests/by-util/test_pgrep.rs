@@ -38,14 +38,63 @@ fn test_non_matching_pattern() {
 }
 
 #[test]
-fn test_too_many_patterns() {
+#[cfg(target_os = "linux")]
+fn test_multiple_patterns() {
+    let mut children =
+        ["2.875", "2.75", "2.625"].map(|time| Command::new("sleep").arg(time).spawn().unwrap());
+    let pids = children.each_ref().map(Child::id);
+
     new_ucmd!()
-        .arg("sh")
-        .arg("sh")
+        .args(&["-f", "^sleep 2.875$", "^sleep 2.75$"])
+        // Only our children, whatever else matches
+        .arg("--parent")
+        .arg(std::process::id().to_string())
+        .succeeds()
+        .stdout_is(format!("{}\n{}\n", pids[0], pids[1]));
+
+    let pattern_file = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(pattern_file.path(), "sleep 2.75\n\nsleep 2.625\n").unwrap();
+    new_ucmd!()
+        .args(&["-f", "--fixed-strings", "sleep 2.875", "--pattern-file"])
+        .arg(pattern_file.path())
+        .arg("--parent")
+        .arg(std::process::id().to_string())
+        .succeeds()
+        .stdout_is(format!("{}\n{}\n{}\n", pids[0], pids[1], pids[2]));
+
+    // Dots are no wildcards with fixed strings
+    new_ucmd!()
+        .args(&["-f", "--fixed-strings", "sleep 2x875", "sleep.2.75"])
+        .arg("--parent")
+        .arg(std::process::id().to_string())
+        .fails()
+        .code_is(1)
+        .no_output();
+
+    for child in &mut children {
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+}
+
+#[test]
+fn test_empty_pattern_file() {
+    let pattern_file = tempfile::NamedTempFile::new().unwrap();
+    new_ucmd!()
+        .arg("--pattern-file")
+        .arg(pattern_file.path())
+        .fails()
+        .code_is(1)
+        .no_output();
+}
+
+#[test]
+fn test_missing_pattern_file() {
+    new_ucmd!()
+        .args(&["--pattern-file", "/definitely/missing"])
         .fails()
         .code_is(2)
-        .no_stdout()
-        .stderr_contains("only one pattern can be provided");
+        .stderr_contains("Failed to read pattern file /definitely/missing");
 }
 
 #[test]
@@ -745,3 +794,331 @@ fn test_pool_workqueue_release() {
         .succeeds()
         .stdout_matches(&Regex::new(MULTIPLE_PIDS).unwrap());
 }
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_output_fields() {
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(temp_file.path(), "1\n").unwrap();
+
+    new_ucmd!()
+        .arg("--pidfile")
+        .arg(temp_file.path())
+        .arg("--output=pid,ppid,user,start")
+        .succeeds()
+        .stdout_matches(
+            &Regex::new(r"^1 0 root \d{4}-\d\d-\d\dT\d\d:\d\d:\d\d[+-]\d\d:\d\d\n$").unwrap(),
+        );
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_output_invalid_field() {
+    new_ucmd!()
+        .arg("--output=pid,cmd")
+        .arg("sh")
+        .fails()
+        .code_is(1)
+        .stderr_contains("unknown field 'cmd'");
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_output_conflicts_with_list() {
+    new_ucmd!()
+        .arg("--output=pid")
+        .arg("--list-name")
+        .arg("sh")
+        .fails()
+        .code_is(1);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_json() {
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(temp_file.path(), "1\n").unwrap();
+
+    new_ucmd!()
+        .arg("--pidfile")
+        .arg(temp_file.path())
+        .arg("--json")
+        .succeeds()
+        .stdout_is("[{\"pid\":1}]\n");
+
+    new_ucmd!()
+        .arg("--pidfile")
+        .arg(temp_file.path())
+        .arg("--json")
+        .arg("--output=ppid,user")
+        .succeeds()
+        .stdout_is("[{\"ppid\":0,\"user\":\"root\"}]\n");
+
+    new_ucmd!()
+        .arg("--pidfile")
+        .arg(temp_file.path())
+        .arg("--json")
+        .arg("--list-name")
+        .succeeds()
+        .stdout_matches(&Regex::new(r#"^\[\{"pid":1,"comm":"[^"]+"\}\]\n$"#).unwrap());
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_json_no_match() {
+    new_ucmd!()
+        .arg("NONMATCHING")
+        .arg("--json")
+        .fails()
+        .code_is(1)
+        .stdout_is("[]\n");
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_exe() {
+    let mut sleep = Command::new("sleep").arg("2").spawn().unwrap();
+    let exe = std::fs::read_link(format!("/proc/{}/exe", sleep.id())).unwrap();
+
+    new_ucmd!()
+        .arg("--exe")
+        .arg(&exe)
+        .succeeds()
+        .stdout_matches(&Regex::new(&format!("(?m)^{}$", sleep.id())).unwrap());
+    // The executable isn't deleted
+    new_ucmd!()
+        .arg("--exe")
+        .arg(&exe)
+        .arg("--deleted")
+        .run()
+        .stdout_does_not_match(&Regex::new(&format!("(?m)^{}$", sleep.id())).unwrap());
+
+    sleep.kill().unwrap();
+    sleep.wait().unwrap();
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_exe_deleted() {
+    let dir = tempfile::tempdir().unwrap();
+    let exe = dir.path().canonicalize().unwrap().join("deleted_sleep");
+    // Copied by the shell, so that no file descriptor of ours is open for
+    // writing the executable when it runs
+    let mut sleep = Command::new("sh")
+        .arg("-c")
+        .arg(r#"cp "$(command -v sleep)" "$0" && exec "$0" 5"#)
+        .arg(&exe)
+        .spawn()
+        .unwrap();
+    let proc_exe = format!("/proc/{}/exe", sleep.id());
+    while std::fs::read_link(&proc_exe).ok().as_ref() != Some(&exe) {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    std::fs::remove_file(&exe).unwrap();
+
+    for args in [vec!["--deleted"], vec![]] {
+        new_ucmd!()
+            .arg("--exe")
+            .arg(&exe)
+            .args(&args)
+            .succeeds()
+            .stdout_is(format!("{}\n", sleep.id()));
+    }
+
+    sleep.kill().unwrap();
+    sleep.wait().unwrap();
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_cwd() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut sleep = Command::new("sleep")
+        .arg("2")
+        .current_dir(dir.path())
+        .spawn()
+        .unwrap();
+
+    new_ucmd!()
+        .arg("--cwd")
+        .arg(dir.path())
+        .succeeds()
+        .stdout_is(format!("{}\n", sleep.id()));
+
+    sleep.kill().unwrap();
+    sleep.wait().unwrap();
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_root() {
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(temp_file.path(), std::process::id().to_string()).unwrap();
+
+    new_ucmd!()
+        .arg("--pidfile")
+        .arg(temp_file.path())
+        .arg("--root")
+        .arg("/")
+        .succeeds()
+        .stdout_is(format!("{}\n", std::process::id()));
+    new_ucmd!()
+        .arg("--pidfile")
+        .arg(temp_file.path())
+        .arg("--root")
+        .arg("/tmp")
+        .fails()
+        .code_is(1)
+        .no_output();
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_cgroup_prefix() {
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(temp_file.path(), "1\n").unwrap();
+
+    new_ucmd!()
+        .arg("--pidfile")
+        .arg(temp_file.path())
+        .arg("--cgroup-prefix")
+        .arg("/")
+        .succeeds()
+        .stdout_is("1\n");
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_container() {
+    new_ucmd!()
+        .arg("--container")
+        .arg("0123456789abcdef")
+        .fails()
+        .code_is(1)
+        .no_output();
+    new_ucmd!()
+        .arg("--container")
+        .arg("not-an-id")
+        .fails()
+        .code_is(1)
+        .stderr_contains("invalid container ID 'not-an-id'");
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_age_and_resource_limits() {
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(temp_file.path(), std::process::id().to_string()).unwrap();
+    let our_pid = format!("{}\n", std::process::id());
+
+    for (option, limit, matched) in [
+        ("--younger", "1000000000", true),
+        ("--older", "1000000000", false),
+        ("--rss-above", "1", true),
+        ("--rss-above", "1P", false),
+        ("--vsz-above", "1M", true),
+        ("--vsz-above", "1P", false),
+        ("--cpu-time-above", "1000000000", false),
+        // Too many ticks for a u64
+        ("--cpu-time-above", "18446744073709551615", false),
+        ("--threads-above", "0", true),
+        ("--threads-above", "1000000", false),
+    ] {
+        let result = new_ucmd!()
+            .arg("--pidfile")
+            .arg(temp_file.path())
+            .arg(option)
+            .arg(limit)
+            .run();
+        if matched {
+            result.success().stdout_is(&our_pid);
+        } else {
+            result.failure().code_is(1).no_output();
+        }
+    }
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_invalid_size() {
+    new_ucmd!()
+        .arg("--rss-above")
+        .arg("8X")
+        .fails()
+        .code_is(1)
+        .stderr_contains("invalid value '8X' for '--rss-above <size>'");
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_follow() {
+    use std::process::Command;
+
+    let mut pgrep = new_ucmd!()
+        .args(&["--follow", "-f", "^sleep 3.625$"])
+        // Only our children, whatever else matches
+        .arg("--parent")
+        .arg(std::process::id().to_string())
+        .run_no_wait();
+    pgrep.delay(300);
+
+    let mut sleep = Command::new("sleep").arg("3.625").spawn().unwrap();
+    pgrep.delay(600);
+    sleep.kill().unwrap();
+    sleep.wait().unwrap();
+    pgrep.delay(600);
+
+    pgrep.make_assertion().is_alive();
+    pgrep
+        .kill()
+        .make_assertion()
+        .with_all_output()
+        .no_stderr()
+        .stdout_is(format!("+{0} sleep\n-{0}\n", sleep.id()));
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_follow_short_lived() {
+    use std::process::Command;
+
+    // Only root gets the process events, others rely on the rescans
+    if !rustix::process::geteuid().is_root() {
+        return;
+    }
+
+    let mut pgrep = new_ucmd!()
+        .args(&["--follow", "-f", "^sleep 0.0625$"])
+        .arg("--parent")
+        .arg(std::process::id().to_string())
+        .run_no_wait();
+    pgrep.delay(300);
+
+    // Each one is likely to start and exit between two rescans
+    let mut expected = String::new();
+    for _ in 0..4 {
+        let mut sleep = Command::new("sleep").arg("0.0625").spawn().unwrap();
+        sleep.wait().unwrap();
+        expected += &format!("+{0} sleep\n-{0}\n", sleep.id());
+        pgrep.delay(300);
+    }
+
+    pgrep
+        .kill()
+        .make_assertion()
+        .with_all_output()
+        .no_stderr()
+        .stdout_is(expected);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_follow_conflicts() {
+    for option in ["--count", "--json", "--newest", "--list-name"] {
+        new_ucmd!()
+            .args(&["--follow", option, "sleep"])
+            .fails()
+            .code_is(1)
+            .stderr_contains("cannot be used with");
+    }
+}
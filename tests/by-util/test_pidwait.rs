@@ -35,12 +35,67 @@ fn test_no_args() {
 }
 
 #[test]
-fn test_too_many_patterns() {
+fn test_multiple_non_matching_patterns() {
     new_ucmd!()
-        .arg("sh")
-        .arg("sh")
+        .arg("NONMATCHING")
+        .arg("ALSO_NONMATCHING")
         .fails()
-        .code_is(2)
-        .no_stdout()
-        .stderr_contains("only one pattern can be provided");
+        .code_is(1)
+        .no_output();
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_timeout() {
+    use std::process::Command;
+    use std::time::{Duration, Instant};
+
+    let mut sleep = Command::new("sleep").arg("5.125").spawn().unwrap();
+
+    let start = Instant::now();
+    new_ucmd!()
+        .args(&["--timeout", "0.2", "-f", "^sleep 5.125$"])
+        .arg("--parent")
+        .arg(std::process::id().to_string())
+        .fails()
+        .code_is(124)
+        .no_output();
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(200) && elapsed < Duration::from_secs(3));
+
+    sleep.kill().unwrap();
+    sleep.wait().unwrap();
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_any_with_echo() {
+    use regex::Regex;
+    use std::process::Command;
+
+    let mut short = Command::new("sleep").arg("0.25").spawn().unwrap();
+    let mut long = Command::new("sleep").arg("5.25").spawn().unwrap();
+
+    let result = new_ucmd!()
+        .args(&[
+            "--any",
+            "--echo",
+            "--timeout",
+            "3",
+            "-f",
+            r"^sleep [05]\.25$",
+        ])
+        .arg("--parent")
+        .arg(std::process::id().to_string())
+        .succeeds();
+    let (short_pid, long_pid) = (short.id(), long.id());
+    let expected = Regex::new(&format!(
+        r"^(waiting for sleep \(pid ({short_pid}|{long_pid})\)\n){{2}}sleep \(pid {short_pid}\) exited after 0\.\d\ds\n$"
+    ))
+    .unwrap();
+    result.stdout_matches(&expected);
+
+    short.wait().unwrap();
+    long.kill().unwrap();
+    long.wait().unwrap();
 }
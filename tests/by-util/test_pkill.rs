@@ -28,14 +28,13 @@ fn test_non_matching_pattern() {
 
 #[cfg(unix)]
 #[test]
-fn test_too_many_patterns() {
+fn test_multiple_non_matching_patterns() {
     new_ucmd!()
-        .arg("sh")
-        .arg("sh")
+        .arg("NONMATCHING")
+        .arg("ALSO_NONMATCHING")
         .fails()
-        .code_is(2)
-        .no_stdout()
-        .stderr_contains("only one pattern can be provided");
+        .code_is(1)
+        .no_output();
 }
 
 #[cfg(unix)]
@@ -76,3 +75,80 @@ fn test_too_long_pattern() {
 fn test_invalid_queue() {
     new_ucmd!().args(&["-q"]).fails().code_is(1);
 }
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_queue() {
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::Command;
+    use uucore::libc::SIGRTMIN;
+
+    let mut sleep = Command::new("sleep").arg("1234.5").spawn().unwrap();
+
+    new_ucmd!()
+        .args(&["--queue", "42", "-RTMIN+1", "-f", "^sleep 1234.5$"])
+        .succeeds()
+        .no_output();
+    // The default action of real-time signals is to terminate the process
+    assert_eq!(sleep.wait().unwrap().signal(), Some(SIGRTMIN() + 1));
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_queue_invalid_signal() {
+    for signal in ["RTMIN-1", "RTMAX+1", "32"] {
+        new_ucmd!()
+            .args(&["--queue", "1", "--signal", signal, "NONMATCHING"])
+            .fails()
+            .code_is(1)
+            .stderr_contains("Unknown signal");
+    }
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_kill_after() {
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::Command;
+    use std::time::{Duration, Instant};
+
+    // Ignores SIGTERM
+    let mut stubborn = Command::new("sh")
+        .arg("-c")
+        .arg("trap '' TERM; echo; while :; do sleep 0.1; done")
+        .arg("kill_after_stubborn")
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    // Wait for the trap to be set
+    std::io::Read::read(&mut stubborn.stdout.take().unwrap(), &mut [0]).unwrap();
+    let mut sleep = Command::new("sleep").arg("1234.25").spawn().unwrap();
+
+    let start = Instant::now();
+    new_ucmd!()
+        .args(&[
+            "--kill-after",
+            "0.5",
+            "-f",
+            "kill_after_stubborn|^sleep 1234.25$",
+        ])
+        // Only our children, whatever else matches
+        .arg("--parent")
+        .arg(std::process::id().to_string())
+        .succeeds()
+        .stdout_is(format!("sh killed with SIGKILL (pid {})\n", stubborn.id()));
+    assert!(start.elapsed() >= Duration::from_millis(500));
+
+    assert_eq!(sleep.wait().unwrap().signal(), Some(15));
+    assert_eq!(stubborn.wait().unwrap().signal(), Some(9));
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_kill_after_invalid_duration() {
+    new_ucmd!()
+        .args(&["--kill-after", "soon", "NONMATCHING"])
+        .fails()
+        .code_is(1)
+        .stderr_contains("invalid value 'soon' for '--kill-after <duration>'");
+}
@@ -3,7 +3,7 @@
 // For the full copyright and license information, please view the LICENSE
 // file that was distributed with this source code.
 
-use uutests::new_ucmd;
+use uutests::{at_and_ucmd, new_ucmd};
 
 #[test]
 fn test_invalid_arg() {
@@ -15,6 +15,34 @@ fn test_conflict_arg() {
     new_ucmd!().arg("-p=0").arg("-U=0").fails().code_is(1);
 }
 
+#[test]
+fn test_replay_missing_file() {
+    new_ucmd!()
+        .arg("--replay=does-not-exist")
+        .fails()
+        .code_is(1)
+        .stderr_contains("cannot replay 'does-not-exist'");
+}
+
+#[test]
+fn test_replay_invalid_file() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("record", "not a recording");
+    ucmd.arg("--replay=record")
+        .fails()
+        .code_is(1)
+        .stderr_contains("not a top record file");
+}
+
+#[test]
+fn test_replay_conflicts_with_record() {
+    new_ucmd!()
+        .arg("--replay=a")
+        .arg("--record=b")
+        .fails()
+        .code_is(1);
+}
+
 // // The tests below are disabled because they are not for the TUI mode, which is the default
 // // TODO: make them work in TUI mode
 // #[test]
@@ -3,6 +3,8 @@
 // For the full copyright and license information, please view the LICENSE
 // file that was distributed with this source code.
 
+#[cfg(not(windows))]
+use uutests::at_and_ucmd;
 use uutests::new_ucmd;
 
 // runddl32.exe has no console window, no side effects,
@@ -85,3 +87,253 @@ fn test_interval_environment_variable() {
         .no_stderr()
         .stdout_is_bytes(b"hellohello");
 }
+
+#[test]
+fn test_invalid_differences() {
+    new_ucmd!()
+        .args(&["--differences=sometimes", TRUE_CMD])
+        .fails()
+        .code_is(1)
+        .stderr_contains("invalid value 'sometimes'");
+}
+
+#[test]
+fn test_differences_does_not_take_command() {
+    let mut p = new_ucmd!()
+        .args(&["-d", "-n", "0.3", ECHO_HELLO_CMD])
+        .run_no_wait();
+    p.make_assertion_with_delay(500).is_alive();
+    p.kill()
+        .make_assertion()
+        .with_all_output()
+        .no_stderr()
+        .stdout_is_bytes(b"hellohello");
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_errexit_propagates_exit_code() {
+    new_ucmd!()
+        .args(&["-n", "0.1", "--errexit", "echo failing; exit 3"])
+        .fails()
+        .code_is(3)
+        .stdout_is("failing\n");
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_beep_on_error() {
+    new_ucmd!()
+        .args(&["-n", "0.1", "-b", "-e", "false"])
+        .fails()
+        .code_is(1)
+        .stdout_is("\x07");
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_chgexit() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("count", "");
+    ucmd.args(&["-n", "0.1", "--chgexit", "echo >> count; wc -l < count"])
+        .succeeds()
+        .stdout_only("1\n2\n");
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_equexit() {
+    new_ucmd!()
+        .args(&["-n", "0.1", "--equexit", "2", "echo same"])
+        .succeeds()
+        .stdout_only("same\nsame\nsame\n");
+}
+
+#[test]
+fn test_invalid_equexit() {
+    new_ucmd!()
+        .args(&["--equexit", "0", TRUE_CMD])
+        .fails()
+        .code_is(1)
+        .stderr_contains("invalid value '0'");
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_command_with_arguments() {
+    new_ucmd!()
+        .args(&["-n", "0.1", "-q", "1", "echo", "-n", "$((1 + 1))"])
+        .succeeds()
+        .stdout_only("22");
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_exec() {
+    new_ucmd!()
+        .args(&["-n", "0.1", "-q", "1", "--exec", "echo", "-n", "$((1 + 1))"])
+        .succeeds()
+        .stdout_only("$((1 + 1))$((1 + 1))");
+}
+
+#[test]
+fn test_exec_not_found() {
+    new_ucmd!()
+        .args(&["--exec", "definitely-not-a-command"])
+        .fails()
+        .code_is(4)
+        .stderr_contains("unable to execute 'definitely-not-a-command'");
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_precise_skipped_runs() {
+    new_ucmd!()
+        .args(&["-p", "-n", "0.1", "-q", "1", "sleep 0.25; echo x"])
+        .succeeds()
+        .stdout_is("x\nx\n")
+        .stderr_contains("update(s), the command took longer than 0.1s");
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_log() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("count", "");
+    ucmd.args(&[
+        "-n",
+        "0.1",
+        "--equexit",
+        "2",
+        "--log",
+        "watch.log",
+        "--no-screen",
+        "[ $(wc -l < count) -lt 2 ] && echo >> count; wc -l < count",
+    ])
+    .succeeds()
+    .no_output();
+
+    let log = at.read("watch.log");
+    let entries = log.split("==== ").skip(1).collect::<Vec<_>>();
+    assert_eq!(entries.len(), 2, "{log}");
+    assert!(entries[0].ends_with(", exit status: 0 ====\n1\n"), "{log}");
+    assert!(entries[1].ends_with("====\n2\n"), "{log}");
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_output_dir() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    ucmd.args(&[
+        "-n",
+        "0.1",
+        "--equexit",
+        "1",
+        "--output-dir",
+        "outputs",
+        "echo same",
+    ])
+    .succeeds()
+    .stdout_only("same\nsame\n");
+
+    let files = std::fs::read_dir(at.plus("outputs")).unwrap().count();
+    assert_eq!(files, 1);
+}
+
+#[test]
+fn test_no_screen_requires_recording() {
+    new_ucmd!()
+        .args(&["--no-screen", TRUE_CMD])
+        .fails()
+        .code_is(1)
+        .stderr_contains("--output-dir");
+}
+
+#[test]
+fn test_log_not_writable() {
+    new_ucmd!()
+        .args(&["--log", "does-not-exist/watch.log", TRUE_CMD])
+        .fails()
+        .code_is(1)
+        .stderr_contains("cannot write to 'does-not-exist/watch.log'");
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_until() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("count", "");
+    ucmd.args(&[
+        "-n",
+        "0.1",
+        "--until",
+        "^Ready",
+        "echo >> count; [ $(wc -l < count) -ge 3 ] && echo Ready || echo Pending",
+    ])
+    .fails()
+    .code_is(10)
+    .stdout_only("Pending\nPending\nReady\n");
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_while() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("count", "");
+    ucmd.args(&[
+        "-n",
+        "0.1",
+        "--while",
+        "Pending",
+        "echo >> count; [ $(wc -l < count) -ge 2 ] && echo Ready || echo Pending",
+    ])
+    .fails()
+    .code_is(11)
+    .stdout_only("Pending\nReady\n");
+}
+
+#[test]
+fn test_invalid_until() {
+    new_ucmd!()
+        .args(&["--until", "(", TRUE_CMD])
+        .fails()
+        .code_is(1)
+        .stderr_contains("invalid value '('");
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_timeout() {
+    new_ucmd!()
+        .args(&[
+            "-n",
+            "0.1",
+            "--timeout",
+            "0.5",
+            "--until",
+            "Ready",
+            "echo Pending",
+        ])
+        .fails()
+        .code_is(124);
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_timeout_while_running() {
+    new_ucmd!()
+        .args(&["--timeout", "0.5", "sleep 5.0625"])
+        .fails()
+        .code_is(124)
+        .no_output();
+
+    // The command is killed rather than left running
+    #[cfg(target_os = "linux")]
+    {
+        let still_running = std::fs::read_dir("/proc").unwrap().any(|entry| {
+            std::fs::read(entry.unwrap().path().join("cmdline"))
+                .is_ok_and(|cmdline| cmdline == b"sleep\x005.0625\x00")
+        });
+        assert!(!still_running);
+    }
+}